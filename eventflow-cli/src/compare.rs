@@ -1,5 +1,5 @@
 use serde_json::Value;
-use std::collections::HashSet;
+use std::collections::{HashMap, HashSet};
 use std::fs::File;
 use std::io::{self, BufRead, BufReader};
 use std::path::{Path, PathBuf};
@@ -9,117 +9,1350 @@ pub struct CompareOptions {
     pub test: PathBuf,
     pub tol_time: f64,
     pub tol_val: f64,
+    pub skip_before: Option<f64>,
+    pub time_key: String,
+    pub unordered: bool,
+    pub match_tiebreak: String,
+    pub require_monotonic: bool,
+    pub per_type: bool,
+    pub ignore_keys: HashSet<String>,
+    pub only_keys: Option<HashSet<String>>,
+    pub rel_tol_time: Option<f64>,
+    pub rel_tol_val: Option<f64>,
+    /// Max mismatch messages to collect before stopping the scan (0 = unlimited).
+    /// Default 1 preserves the original fail-at-first-mismatch behavior.
+    pub max_errors: usize,
+    /// When set, events are grouped into `(type, tol_time-wide bucket)` keys
+    /// and compared as per-bucket multisets instead of positionally. Takes
+    /// precedence over `unordered` if both are set. Default false keeps the
+    /// original strict positional behavior.
+    pub match_by_key: bool,
+    /// Per-payload-key absolute tolerance overrides, e.g. `{"count": 1.0}`.
+    /// A key present here overrides `tol_val` for that key only; all other
+    /// numeric keys keep using `tol_val`. A key that never appears in either
+    /// payload is simply never consulted, not an error.
+    pub tolerance_keys: HashMap<String, f64>,
+    /// Per-payload-key constant bias, e.g. `{"temp": 2.5}`: subtracted from
+    /// the test value for that key before the tolerance check, to absorb a
+    /// known systematic calibration offset without touching either file.
+    pub value_offset_keys: HashMap<String, f64>,
+    /// Payload keys whose bias should instead be estimated automatically as
+    /// the median `test - gold` difference across the events positionally
+    /// paired by index (the same pairing `gold_events`/`test_events` already
+    /// share, since their lengths must match). Estimated offsets are merged
+    /// into `value_offset_keys` before the real comparison runs, and the
+    /// values used are reported back via `Summary`/`CompareError::Mismatch`.
+    pub auto_value_offset_keys: HashSet<String>,
 }
 
+/// A delta passes if it is within the absolute tolerance OR, when a relative
+/// tolerance is configured, within `rel * max(|gold|, |test|)`. Either check
+/// passing counts as a match, so tightening one mode never breaks traces
+/// that were already passing the other.
+fn within_tolerance(delta: f64, gold: f64, test: f64, abs_tol: f64, rel_tol: Option<f64>) -> bool {
+    if delta <= abs_tol {
+        return true;
+    }
+    match rel_tol {
+        Some(rel) => delta <= rel * gold.abs().max(test.abs()),
+        None => false,
+    }
+}
+
+/// Buckets a timestamp into a `tol_time`-wide integer bin for `--match-by-key`
+/// grouping. `tol_time == 0` falls back to the timestamp's exact bit pattern
+/// so only bit-identical timestamps share a bucket, matching the intuition
+/// that a zero tolerance means "exact".
+fn bucket_key(t: f64, tol_time: f64) -> i64 {
+    if tol_time > 0.0 {
+        (t / tol_time).round() as i64
+    } else {
+        t.to_bits() as i64
+    }
+}
+
+/// Applies `--only-keys` (if set) then `--ignore-keys` to a set of candidate
+/// payload keys, in that order, so a key must survive the whitelist before
+/// the blacklist can exclude it.
+fn filter_keys<'a>(keys: HashSet<&'a str>, opts: &CompareOptions) -> HashSet<&'a str> {
+    let keys: HashSet<&str> = match &opts.only_keys {
+        Some(only) => keys.into_iter().filter(|k| only.contains(*k)).collect(),
+        None => keys,
+    };
+    keys.into_iter().filter(|k| !opts.ignore_keys.contains(*k)).collect()
+}
+
+/// A single mismatched event, structured so callers (e.g. `--format json`)
+/// can consume it without scraping the human-readable `reason` text.
+#[derive(serde::Serialize)]
+pub struct Mismatch {
+    pub idx: usize,
+    pub kind: String,
+    pub reason: String,
+    pub time_delta: Option<f64>,
+    pub value_deltas: HashMap<String, f64>,
+}
+
+/// Error returned by `run`: either a fatal setup failure (bad file, length
+/// mismatch, invalid option) with no per-event structure, or one or more
+/// structured per-event mismatches plus the per-type tally. `Mismatch` also
+/// carries the same run-level counts `Summary` would have reported on
+/// success, so a caller (e.g. `--format json`) can report a complete result
+/// object on either outcome.
+pub enum CompareError {
+    Fatal(String),
+    Mismatch {
+        events: usize,
+        meta_gold: usize,
+        meta_test: usize,
+        tol_time: f64,
+        tol_val: f64,
+        mismatches: Vec<Mismatch>,
+        type_counts: HashMap<String, (usize, usize)>,
+        gold_parse_skips: Vec<ParseSkip>,
+        test_parse_skips: Vec<ParseSkip>,
+        value_offsets_used: HashMap<String, f64>,
+    },
+}
+
+/// Renders "N line(s) skipped in {label} (lines a, b, c)" for the Display
+/// impl and the text output of `ef compare`.
+pub fn format_parse_skips(label: &str, skips: &[ParseSkip]) -> String {
+    let nums: Vec<String> = skips.iter().map(|s| s.line_no.to_string()).collect();
+    format!("{} line(s) skipped in {label} (lines {})", skips.len(), nums.join(", "))
+}
+
+impl std::fmt::Display for CompareError {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            CompareError::Fatal(msg) => write!(f, "{msg}"),
+            CompareError::Mismatch {
+                mismatches,
+                type_counts,
+                gold_parse_skips,
+                test_parse_skips,
+                value_offsets_used,
+                ..
+            } => {
+                let mut lines: Vec<String> = mismatches.iter().map(|m| m.reason.clone()).collect();
+                if !gold_parse_skips.is_empty() {
+                    lines.push(format_parse_skips("gold", gold_parse_skips));
+                }
+                if !test_parse_skips.is_empty() {
+                    lines.push(format_parse_skips("test", test_parse_skips));
+                }
+                if !value_offsets_used.is_empty() {
+                    let mut keys: Vec<_> = value_offsets_used.keys().collect();
+                    keys.sort();
+                    for key in keys {
+                        lines.push(format!("value_offset[{key}]={}", value_offsets_used[key]));
+                    }
+                }
+                if !type_counts.is_empty() {
+                    lines.push(format_type_breakdown(type_counts));
+                }
+                write!(f, "{}", lines.join("\n"))
+            }
+        }
+    }
+}
+
+#[derive(serde::Serialize)]
 pub struct Summary {
     pub events: usize,
     pub meta_gold: usize,
     pub meta_test: usize,
     pub tol_time: f64,
     pub tol_val: f64,
+    pub rel_tol_time: Option<f64>,
+    pub rel_tol_val: Option<f64>,
+    pub skipped_gold: usize,
+    pub skipped_test: usize,
+    pub max_time_delta: f64,
+    pub max_value_deltas: std::collections::HashMap<String, f64>,
+    /// Per-`type` (matched, mismatched) tallies, populated only when `per_type` is set.
+    pub type_counts: HashMap<String, (usize, usize)>,
+    /// Lines in `gold`/`test` that `parse_file` couldn't classify (invalid
+    /// JSON, missing/unknown `type`) and silently dropped rather than
+    /// comparing. Non-empty here means the comparison ran over fewer events
+    /// than the file actually contains — worth surfacing even on an
+    /// otherwise-passing run, since a truncated file can "pass" if both
+    /// sides happen to drop the same trailing garbage.
+    pub gold_parse_skips: Vec<ParseSkip>,
+    pub test_parse_skips: Vec<ParseSkip>,
+    /// Per-key value offsets actually applied during the comparison: the
+    /// union of `--value-offset-key` and any `--auto-value-offset` keys,
+    /// with the latter's median-estimated bias substituted in.
+    pub value_offsets_used: HashMap<String, f64>,
+}
+
+/// Estimates each `key`'s constant bias as the median of `test - gold` across
+/// events positionally paired by index (gold[i] vs test[i]), skipping pairs
+/// where the key is absent or non-numeric on either side. A key with no
+/// usable pairs is simply omitted rather than reported as a zero offset.
+fn estimate_auto_value_offsets(
+    gold_events: &[Value],
+    test_events: &[Value],
+    keys: &HashSet<String>,
+) -> HashMap<String, f64> {
+    let mut diffs: HashMap<&str, Vec<f64>> = HashMap::new();
+    for key in keys {
+        diffs.insert(key.as_str(), Vec::new());
+    }
+    for (g, t) in gold_events.iter().zip(test_events.iter()) {
+        let (Some(pg), Some(pt)) =
+            (g.get("payload").and_then(|v| v.as_object()), t.get("payload").and_then(|v| v.as_object()))
+        else {
+            continue;
+        };
+        for key in keys {
+            if let (Some(ng), Some(nt)) =
+                (pg.get(key.as_str()).and_then(|v| v.as_f64()), pt.get(key.as_str()).and_then(|v| v.as_f64()))
+            {
+                if ng.is_finite() && nt.is_finite() {
+                    diffs.get_mut(key.as_str()).unwrap().push(nt - ng);
+                }
+            }
+        }
+    }
+    diffs
+        .into_iter()
+        .filter_map(|(key, mut values)| {
+            if values.is_empty() {
+                return None;
+            }
+            values.sort_by(|a, b| a.partial_cmp(b).unwrap());
+            let mid = values.len() / 2;
+            let median = if values.len() % 2 == 0 { (values[mid - 1] + values[mid]) / 2.0 } else { values[mid] };
+            Some((key.to_string(), median))
+        })
+        .collect()
+}
+
+#[allow(clippy::result_large_err)]
+pub fn run(opts: CompareOptions) -> Result<Summary, CompareError> {
+    run_with_progress(opts, 0, |_index, _matched_so_far| {})
 }
 
-pub fn run(opts: CompareOptions) -> Result<Summary, String> {
-    let (gold_events, gold_meta) =
-        parse_file(&opts.gold).map_err(|e| format!("Failed to read gold file: {e}"))?;
-    let (test_events, test_meta) =
-        parse_file(&opts.test).map_err(|e| format!("Failed to read test file: {e}"))?;
+/// Same as `run`, but for library embedders running a very long comparison
+/// (e.g. a GUI) who want live progress instead of blocking until the whole
+/// match completes: `on_progress(index, matched_so_far)` is invoked every
+/// `progress_every` gold events scanned during the match phase (`index` is
+/// the gold index just scanned, `matched_so_far` the running count of
+/// matches). `progress_every == 0` disables the callback entirely (the
+/// path `run` always takes), so adding a callback never changes behavior
+/// for callers that don't want one.
+#[allow(clippy::result_large_err)]
+pub fn run_with_progress(
+    mut opts: CompareOptions,
+    progress_every: usize,
+    mut on_progress: impl FnMut(usize, usize),
+) -> Result<Summary, CompareError> {
+    if !opts.unordered && !opts.match_by_key && opts.auto_value_offset_keys.is_empty() {
+        return run_ordered_streaming(&opts, progress_every, &mut on_progress);
+    }
+
+    let (mut gold_events, gold_meta, gold_parse_skips) = parse_file(&opts.gold)
+        .map_err(|e| CompareError::Fatal(format!("Failed to read gold file: {e}")))?;
+    let (mut test_events, test_meta, test_parse_skips) = parse_file(&opts.test)
+        .map_err(|e| CompareError::Fatal(format!("Failed to read test file: {e}")))?;
+
+    let time_key = opts.time_key.as_str();
+
+    let mut skipped_gold = 0usize;
+    let mut skipped_test = 0usize;
+    if let Some(skip_before) = opts.skip_before {
+        let before_gold = gold_events.len();
+        gold_events.retain(|e| get_f64(e, time_key).map(|t| t >= skip_before).unwrap_or(true));
+        skipped_gold = before_gold - gold_events.len();
+
+        let before_test = test_events.len();
+        test_events.retain(|e| get_f64(e, time_key).map(|t| t >= skip_before).unwrap_or(true));
+        skipped_test = before_test - test_events.len();
+    }
+
+    if opts.require_monotonic {
+        check_monotonic(&gold_events, time_key, "gold").map_err(CompareError::Fatal)?;
+        check_monotonic(&test_events, time_key, "test").map_err(CompareError::Fatal)?;
+    }
 
     if gold_events.len() != test_events.len() {
-        return Err(format!(
+        return Err(CompareError::Fatal(format!(
             "Event length mismatch: gold={} test={}",
             gold_events.len(),
             test_events.len()
-        ));
+        )));
+    }
+
+    if !opts.auto_value_offset_keys.is_empty() {
+        let estimated = estimate_auto_value_offsets(&gold_events, &test_events, &opts.auto_value_offset_keys);
+        opts.value_offset_keys.extend(estimated);
+    }
+    let value_offsets_used = opts.value_offset_keys.clone();
+
+    if opts.unordered
+        && opts.match_tiebreak != "nearest-time"
+        && opts.match_tiebreak != "first"
+        && opts.match_tiebreak != "best-value"
+    {
+        return Err(CompareError::Fatal(format!(
+            "match_tiebreak must be 'nearest-time', 'first', or 'best-value' (got '{}')",
+            opts.match_tiebreak
+        )));
+    }
+
+    let (max_time_delta, max_value_deltas, type_counts, mismatches) = if opts.match_by_key {
+        match_by_key(&gold_events, &test_events, time_key, &opts, progress_every, &mut on_progress)
+            .map_err(CompareError::Fatal)?
+    } else if opts.unordered {
+        match_unordered(&gold_events, &test_events, time_key, &opts, progress_every, &mut on_progress)
+            .map_err(CompareError::Fatal)?
+    } else {
+        match_ordered(&gold_events, &test_events, time_key, &opts, progress_every, &mut on_progress)
+            .map_err(CompareError::Fatal)?
+    };
+
+    if !mismatches.is_empty() {
+        return Err(CompareError::Mismatch {
+            events: gold_events.len(),
+            meta_gold: gold_meta.len(),
+            meta_test: test_meta.len(),
+            tol_time: opts.tol_time,
+            tol_val: opts.tol_val,
+            mismatches,
+            type_counts,
+            gold_parse_skips,
+            test_parse_skips,
+            value_offsets_used,
+        });
+    }
+
+    Ok(Summary {
+        events: gold_events.len(),
+        meta_gold: gold_meta.len(),
+        meta_test: test_meta.len(),
+        tol_time: opts.tol_time,
+        tol_val: opts.tol_val,
+        rel_tol_time: opts.rel_tol_time,
+        rel_tol_val: opts.rel_tol_val,
+        skipped_gold,
+        skipped_test,
+        max_time_delta,
+        max_value_deltas,
+        type_counts,
+        gold_parse_skips,
+        test_parse_skips,
+        value_offsets_used,
+    })
+}
+
+/// Renders a sorted `type => matched/mismatched` breakdown, one line per type.
+fn format_type_breakdown(type_counts: &HashMap<String, (usize, usize)>) -> String {
+    let mut keys: Vec<_> = type_counts.keys().collect();
+    keys.sort();
+    let mut out = String::from("Per-type breakdown:");
+    for k in keys {
+        let (matched, mismatched) = type_counts[k];
+        out.push_str(&format!("\n  {k}: matched={matched} mismatched={mismatched}"));
+    }
+    out
+}
+
+type MatchOutcome = (f64, HashMap<String, f64>, HashMap<String, (usize, usize)>, Vec<Mismatch>);
+
+/// Records a per-type match/mismatch tally, and accumulates structured
+/// mismatches (capped at `opts.max_errors`, 0 meaning unlimited) instead of
+/// stopping at the first one. Returns `true` when the caller should stop
+/// scanning: with `--per-type` the scan always continues to completion for a
+/// full breakdown; otherwise it stops as soon as the cap is reached, so
+/// `--max-errors 1` (the default) preserves the original fail-fast behavior
+/// exactly.
+#[allow(clippy::too_many_arguments)]
+fn record_outcome(
+    opts: &CompareOptions,
+    idx: usize,
+    kind: &str,
+    ok: bool,
+    reason: String,
+    time_delta: Option<f64>,
+    value_deltas: HashMap<String, f64>,
+    type_counts: &mut HashMap<String, (usize, usize)>,
+    mismatches: &mut Vec<Mismatch>,
+) -> bool {
+    if opts.per_type {
+        let entry = type_counts.entry(kind.to_string()).or_insert((0, 0));
+        if ok {
+            entry.0 += 1;
+        } else {
+            entry.1 += 1;
+            if opts.max_errors == 0 || mismatches.len() < opts.max_errors {
+                mismatches.push(Mismatch { idx, kind: kind.to_string(), reason, time_delta, value_deltas });
+            }
+        }
+        false
+    } else if ok {
+        false
+    } else {
+        mismatches.push(Mismatch { idx, kind: kind.to_string(), reason, time_delta, value_deltas });
+        opts.max_errors != 0 && mismatches.len() >= opts.max_errors
     }
+}
+
+/// Strict in-order comparison: pairs gold[i] with test[i] directly.
+#[allow(clippy::too_many_arguments)]
+fn match_ordered(
+    gold_events: &[Value],
+    test_events: &[Value],
+    time_key: &str,
+    opts: &CompareOptions,
+    progress_every: usize,
+    on_progress: &mut dyn FnMut(usize, usize),
+) -> Result<MatchOutcome, String> {
+    let mut max_time_delta = 0.0f64;
+    let mut max_value_deltas: HashMap<String, f64> = HashMap::new();
+    let mut type_counts: HashMap<String, (usize, usize)> = HashMap::new();
+    let mut mismatches: Vec<Mismatch> = Vec::new();
+    let mut matched_so_far = 0usize;
 
     for (i, (g, t)) in gold_events.iter().zip(test_events.iter()).enumerate() {
         let kind_g = g.get("type").and_then(|v| v.as_str()).unwrap_or("?");
         let kind_t = t.get("type").and_then(|v| v.as_str()).unwrap_or("?");
 
         if kind_g != kind_t {
-            return Err(format!(
-                "Mismatch at idx={i}: kind gold='{kind_g}' test='{kind_t}'"
-            ));
+            if record_outcome(
+                opts,
+                i,
+                kind_g,
+                false,
+                format!("Mismatch at idx={i}: kind gold='{kind_g}' test='{kind_t}'"),
+                None,
+                HashMap::new(),
+                &mut type_counts,
+                &mut mismatches,
+            ) {
+                break;
+            }
+            if progress_every != 0 && (i + 1) % progress_every == 0 {
+                on_progress(i, matched_so_far);
+            }
+            continue;
         }
 
-        let tg = get_f64(g, "t_s").map_err(|_| format!("Missing/invalid t_s in gold at idx={i}"))?;
-        let tt = get_f64(t, "t_s").map_err(|_| format!("Missing/invalid t_s in test at idx={i}"))?;
+        let tg = get_f64(g, time_key).map_err(|_| format!("Missing/invalid {time_key} in gold at idx={i}"))?;
+        let tt = get_f64(t, time_key).map_err(|_| format!("Missing/invalid {time_key} in test at idx={i}"))?;
         let dt = (tg - tt).abs();
-        if dt > opts.tol_time {
-            return Err(format!(
-                "Time mismatch at idx={i}: kind={kind_g} t_s_gold={tg} t_s_test={tt} Δt={dt} > tol_time={}",
-                opts.tol_time
-            ));
-        }
-
-        // Optional numeric payload comparison (non-fatal if absent).
-        if let (Some(pg), Some(pt)) = (
-            g.get("payload").and_then(|v| v.as_object()),
-            t.get("payload").and_then(|v| v.as_object()),
-        ) {
-            // Intersect keys that are numeric on both sides
-            let keys_g: HashSet<_> = pg
-                .iter()
-                .filter(|(_, v)| v.is_number())
-                .map(|(k, _)| k.as_str())
-                .collect();
-            let keys_t: HashSet<_> = pt
-                .iter()
-                .filter(|(_, v)| v.is_number())
-                .map(|(k, _)| k.as_str())
-                .collect();
-            for &k in keys_g.intersection(&keys_t) {
-                let ng = pg.get(k).and_then(|v| v.as_f64()).unwrap_or(f64::NAN);
-                let nt = pt.get(k).and_then(|v| v.as_f64()).unwrap_or(f64::NAN);
-                if ng.is_finite() && nt.is_finite() {
-                    let dv = (ng - nt).abs();
-                    if dv > opts.tol_val {
-                        return Err(format!(
-                            "Payload numeric mismatch at idx={i}: kind={kind_g} key='{k}' gold={ng} test={nt} Δ={dv} > tol_val={}",
-                            opts.tol_val
-                        ));
+        if dt > max_time_delta {
+            max_time_delta = dt;
+        }
+        if !within_tolerance(dt, tg, tt, opts.tol_time, opts.rel_tol_time) {
+            if record_outcome(
+                opts,
+                i,
+                kind_g,
+                false,
+                format!(
+                    "Time mismatch at idx={i}: kind={kind_g} {time_key}_gold={tg} {time_key}_test={tt} Δt={dt} > tol_time={}",
+                    opts.tol_time
+                ),
+                Some(dt),
+                HashMap::new(),
+                &mut type_counts,
+                &mut mismatches,
+            ) {
+                break;
+            }
+            if progress_every != 0 && (i + 1) % progress_every == 0 {
+                on_progress(i, matched_so_far);
+            }
+            continue;
+        }
+
+        let stop = match check_payload(g, t, opts, &mut max_value_deltas) {
+            Ok(()) => {
+                matched_so_far += 1;
+                record_outcome(
+                    opts, i, kind_g, true, String::new(), None, HashMap::new(), &mut type_counts, &mut mismatches,
+                )
+            }
+            Err((k, ng, nt, dv)) => record_outcome(
+                opts,
+                i,
+                kind_g,
+                false,
+                format!(
+                    "Payload numeric mismatch at idx={i}: kind={kind_g} key='{k}' gold={ng} test={nt} Δ={dv} > tol_val={}",
+                    opts.tol_val
+                ),
+                None,
+                HashMap::from([(k, dv)]),
+                &mut type_counts,
+                &mut mismatches,
+            ),
+        };
+        if progress_every != 0 && (i + 1) % progress_every == 0 {
+            on_progress(i, matched_so_far);
+        }
+        if stop {
+            break;
+        }
+    }
+
+    Ok((max_time_delta, max_value_deltas, type_counts, mismatches))
+}
+
+/// Unordered comparison: for each gold event (in order), picks an unconsumed
+/// test event of the same type within `tol_time`, per `match_tiebreak`:
+/// - "nearest-time": the candidate with the smallest |Δt| (default)
+/// - "first": the lowest-index unconsumed candidate
+/// - "best-value": the candidate with the smallest summed numeric payload
+///   delta, falling back to nearest-time to break remaining ties
+///
+/// This pins down a deterministic match once multiple test events fall
+/// within tolerance of the same gold event, so results are reproducible.
+#[allow(clippy::too_many_arguments)]
+fn match_unordered(
+    gold_events: &[Value],
+    test_events: &[Value],
+    time_key: &str,
+    opts: &CompareOptions,
+    progress_every: usize,
+    on_progress: &mut dyn FnMut(usize, usize),
+) -> Result<MatchOutcome, String> {
+    let mut max_time_delta = 0.0f64;
+    let mut max_value_deltas: HashMap<String, f64> = HashMap::new();
+    let mut type_counts: HashMap<String, (usize, usize)> = HashMap::new();
+    let mut mismatches: Vec<Mismatch> = Vec::new();
+    let mut consumed = vec![false; test_events.len()];
+    let mut matched_so_far = 0usize;
+
+    for (i, g) in gold_events.iter().enumerate() {
+        let kind_g = g.get("type").and_then(|v| v.as_str()).unwrap_or("?");
+        let tg = get_f64(g, time_key).map_err(|_| format!("Missing/invalid {time_key} in gold at idx={i}"))?;
+
+        let mut best: Option<(usize, f64, f64)> = None; // (index, dt, value_delta)
+        for (j, t) in test_events.iter().enumerate() {
+            if consumed[j] {
+                continue;
+            }
+            let kind_t = t.get("type").and_then(|v| v.as_str()).unwrap_or("?");
+            if kind_t != kind_g {
+                continue;
+            }
+            let Ok(tt) = get_f64(t, time_key) else { continue };
+            let dt = (tg - tt).abs();
+            if !within_tolerance(dt, tg, tt, opts.tol_time, opts.rel_tol_time) {
+                continue;
+            }
+            let vd = summed_value_delta(g, t, opts);
+            let better = match (&best, opts.match_tiebreak.as_str()) {
+                (None, _) => true,
+                (Some((_, best_dt, _)), "nearest-time") => dt < *best_dt,
+                (Some(_), "first") => false,
+                (Some((_, best_dt, best_vd)), "best-value") => vd < *best_vd || (vd == *best_vd && dt < *best_dt),
+                _ => false,
+            };
+            if better {
+                best = Some((j, dt, vd));
+            }
+        }
+
+        let Some((j, dt, _)) = best else {
+            if record_outcome(
+                opts,
+                i,
+                kind_g,
+                false,
+                format!(
+                    "No unconsumed test event of kind='{kind_g}' within tol_time={} for gold idx={i}",
+                    opts.tol_time
+                ),
+                None,
+                HashMap::new(),
+                &mut type_counts,
+                &mut mismatches,
+            ) {
+                break;
+            }
+            if progress_every != 0 && (i + 1) % progress_every == 0 {
+                on_progress(i, matched_so_far);
+            }
+            continue;
+        };
+        consumed[j] = true;
+        if dt > max_time_delta {
+            max_time_delta = dt;
+        }
+        let stop = match check_payload(g, &test_events[j], opts, &mut max_value_deltas) {
+            Ok(()) => {
+                matched_so_far += 1;
+                record_outcome(
+                    opts, i, kind_g, true, String::new(), None, HashMap::new(), &mut type_counts, &mut mismatches,
+                )
+            }
+            Err((k, ng, nt, dv)) => record_outcome(
+                opts,
+                i,
+                kind_g,
+                false,
+                format!(
+                    "Payload numeric mismatch at idx={i}: kind={kind_g} key='{k}' gold={ng} test={nt} Δ={dv} > tol_val={}",
+                    opts.tol_val
+                ),
+                Some(dt),
+                HashMap::from([(k, dv)]),
+                &mut type_counts,
+                &mut mismatches,
+            ),
+        };
+        if progress_every != 0 && (i + 1) % progress_every == 0 {
+            on_progress(i, matched_so_far);
+        }
+        if stop {
+            break;
+        }
+    }
+
+    Ok((max_time_delta, max_value_deltas, type_counts, mismatches))
+}
+
+/// Order-independent comparison for `--match-by-key`: events are grouped into
+/// `(type, tol_time-wide bucket)` keys (see `bucket_key`) and the two sides'
+/// buckets are compared as multisets instead of pairing gold[i] with test[i]
+/// positionally, so equal-timestamp events that arrive in a different but
+/// still-correct relative order don't produce false mismatches. A bucket
+/// whose gold/test counts differ has no single offending event to blame, so
+/// it's reported as one mismatch naming the bucket time and the surplus
+/// (gold_count > test_count) or missing (gold_count < test_count) count.
+/// Buckets with matching counts are paired in file order and checked exactly
+/// like the positional path (time + payload tolerances).
+#[allow(clippy::too_many_arguments)]
+fn match_by_key(
+    gold_events: &[Value],
+    test_events: &[Value],
+    time_key: &str,
+    opts: &CompareOptions,
+    progress_every: usize,
+    on_progress: &mut dyn FnMut(usize, usize),
+) -> Result<MatchOutcome, String> {
+    let mut max_time_delta = 0.0f64;
+    let mut max_value_deltas: HashMap<String, f64> = HashMap::new();
+    let mut type_counts: HashMap<String, (usize, usize)> = HashMap::new();
+    let mut mismatches: Vec<Mismatch> = Vec::new();
+    let mut matched_so_far = 0usize;
+    let mut scanned = 0usize;
+
+    let mut gold_buckets: HashMap<(String, i64), Vec<(usize, f64)>> = HashMap::new();
+    for (i, g) in gold_events.iter().enumerate() {
+        let kind = g.get("type").and_then(|v| v.as_str()).unwrap_or("?").to_string();
+        let t = get_f64(g, time_key).map_err(|_| format!("Missing/invalid {time_key} in gold at idx={i}"))?;
+        gold_buckets.entry((kind, bucket_key(t, opts.tol_time))).or_default().push((i, t));
+    }
+    let mut test_buckets: HashMap<(String, i64), Vec<(usize, f64)>> = HashMap::new();
+    for (j, t) in test_events.iter().enumerate() {
+        let kind = t.get("type").and_then(|v| v.as_str()).unwrap_or("?").to_string();
+        let tt = get_f64(t, time_key).map_err(|_| format!("Missing/invalid {time_key} in test at idx={j}"))?;
+        test_buckets.entry((kind, bucket_key(tt, opts.tol_time))).or_default().push((j, tt));
+    }
+
+    let mut keys: Vec<(String, i64)> = gold_buckets.keys().cloned().collect();
+    for k in test_buckets.keys() {
+        if !gold_buckets.contains_key(k) {
+            keys.push(k.clone());
+        }
+    }
+    keys.sort();
+
+    'outer: for key in keys {
+        let kind = key.0.clone();
+        let gold_hits = gold_buckets.get(&key).cloned().unwrap_or_default();
+        let test_hits = test_buckets.get(&key).cloned().unwrap_or_default();
+        let bucket_t = gold_hits.first().or(test_hits.first()).map(|(_, t)| *t).unwrap_or(0.0);
+
+        if gold_hits.len() != test_hits.len() {
+            let idx = gold_hits.first().or(test_hits.first()).map(|(i, _)| *i).unwrap_or(0);
+            let stop = record_outcome(
+                opts,
+                idx,
+                &kind,
+                false,
+                format!(
+                    "Bucket mismatch at {time_key}~={bucket_t}: kind={kind} gold_count={} test_count={} ({})",
+                    gold_hits.len(),
+                    test_hits.len(),
+                    if gold_hits.len() > test_hits.len() {
+                        format!("surplus={}", gold_hits.len() - test_hits.len())
+                    } else {
+                        format!("missing={}", test_hits.len() - gold_hits.len())
                     }
+                ),
+                None,
+                HashMap::new(),
+                &mut type_counts,
+                &mut mismatches,
+            );
+            scanned += gold_hits.len().max(test_hits.len());
+            if progress_every != 0 && scanned.is_multiple_of(progress_every) {
+                on_progress(scanned, matched_so_far);
+            }
+            if stop {
+                break 'outer;
+            }
+            continue;
+        }
+
+        // True positional pairing would re-introduce the false mismatches
+        // this mode exists to avoid, so within each same-size bucket, greedily
+        // pair each gold event with whichever unconsumed test event has the
+        // smallest payload delta (ties broken by encounter order) instead of
+        // pairing by file order.
+        let test_pool = test_hits.clone();
+        let mut consumed_local = vec![false; test_pool.len()];
+        let mut pairs: Vec<((usize, f64), (usize, f64))> = Vec::with_capacity(gold_hits.len());
+        for &(gi, gt) in &gold_hits {
+            let mut best: Option<(usize, f64)> = None;
+            for (local_j, &(ti, _tt)) in test_pool.iter().enumerate() {
+                if consumed_local[local_j] {
+                    continue;
+                }
+                let vd = summed_value_delta(&gold_events[gi], &test_events[ti], opts);
+                if best.map(|(_, best_vd)| vd < best_vd).unwrap_or(true) {
+                    best = Some((local_j, vd));
+                }
+            }
+            let (local_j, _) = best.expect("equal bucket sizes guarantee an unconsumed test event remains");
+            consumed_local[local_j] = true;
+            pairs.push(((gi, gt), test_pool[local_j]));
+        }
+
+        for ((gi, gt), (ti, tt)) in pairs.iter() {
+            let dt = (gt - tt).abs();
+            if dt > max_time_delta {
+                max_time_delta = dt;
+            }
+            let stop = match check_payload(&gold_events[*gi], &test_events[*ti], opts, &mut max_value_deltas) {
+                Ok(()) => {
+                    matched_so_far += 1;
+                    record_outcome(
+                        opts, *gi, &kind, true, String::new(), None, HashMap::new(), &mut type_counts, &mut mismatches,
+                    )
                 }
+                Err((k, ng, nt, dv)) => record_outcome(
+                    opts,
+                    *gi,
+                    &kind,
+                    false,
+                    format!(
+                        "Payload numeric mismatch at idx={gi}: kind={kind} key='{k}' gold={ng} test={nt} Δ={dv} > tol_val={}",
+                        opts.tol_val
+                    ),
+                    Some(dt),
+                    HashMap::from([(k, dv)]),
+                    &mut type_counts,
+                    &mut mismatches,
+                ),
+            };
+            scanned += 1;
+            if progress_every != 0 && scanned.is_multiple_of(progress_every) {
+                on_progress(scanned, matched_so_far);
+            }
+            if stop {
+                break 'outer;
             }
         }
     }
 
-    Ok(Summary {
-        events: gold_events.len(),
-        meta_gold: gold_meta.len(),
-        meta_test: test_meta.len(),
-        tol_time: opts.tol_time,
-        tol_val: opts.tol_val,
+    Ok((max_time_delta, max_value_deltas, type_counts, mismatches))
+}
+
+/// Sum of |gold - test| over payload keys numeric on both sides (0.0 if either
+/// side has no numeric payload, so traces without payloads still compare on time alone).
+fn summed_value_delta(g: &Value, t: &Value, opts: &CompareOptions) -> f64 {
+    let (Some(pg), Some(pt)) = (
+        g.get("payload").and_then(|v| v.as_object()),
+        t.get("payload").and_then(|v| v.as_object()),
+    ) else {
+        return 0.0;
+    };
+    let keys_g: HashSet<_> = pg.iter().filter(|(_, v)| v.is_number()).map(|(k, _)| k.as_str()).collect();
+    let keys_t: HashSet<_> = pt.iter().filter(|(_, v)| v.is_number()).map(|(k, _)| k.as_str()).collect();
+    let keys_g = filter_keys(keys_g, opts);
+    keys_g
+        .intersection(&keys_t)
+        .filter_map(|&k| {
+            let ng = pg.get(k).and_then(|v| v.as_f64())?;
+            let nt = pt.get(k).and_then(|v| v.as_f64())?;
+            if ng.is_finite() && nt.is_finite() { Some((ng - nt).abs()) } else { None }
+        })
+        .sum()
+}
+
+/// Checks numeric payload fields common to both events against `tol_val`,
+/// tracking the per-key running max delta. Non-fatal if no payload present.
+/// On mismatch, returns the offending key, gold/test values, and delta so the
+/// caller can build a human-readable reason and a structured `Mismatch`.
+fn check_payload(
+    g: &Value,
+    t: &Value,
+    opts: &CompareOptions,
+    max_value_deltas: &mut HashMap<String, f64>,
+) -> Result<(), (String, f64, f64, f64)> {
+    if let (Some(pg), Some(pt)) = (
+        g.get("payload").and_then(|v| v.as_object()),
+        t.get("payload").and_then(|v| v.as_object()),
+    ) {
+        let keys_g: HashSet<_> = pg.iter().filter(|(_, v)| v.is_number()).map(|(k, _)| k.as_str()).collect();
+        let keys_t: HashSet<_> = pt.iter().filter(|(_, v)| v.is_number()).map(|(k, _)| k.as_str()).collect();
+        let keys_g = filter_keys(keys_g, opts);
+        for &k in keys_g.intersection(&keys_t) {
+            let ng = pg.get(k).and_then(|v| v.as_f64()).unwrap_or(f64::NAN);
+            let nt_raw = pt.get(k).and_then(|v| v.as_f64()).unwrap_or(f64::NAN);
+            let nt = nt_raw - opts.value_offset_keys.get(k).copied().unwrap_or(0.0);
+            if ng.is_finite() && nt.is_finite() {
+                let dv = (ng - nt).abs();
+                let entry = max_value_deltas.entry(k.to_string()).or_insert(0.0);
+                if dv > *entry {
+                    *entry = dv;
+                }
+                let tol_val = opts.tolerance_keys.get(k).copied().unwrap_or(opts.tol_val);
+                if !within_tolerance(dv, ng, nt, tol_val, opts.rel_tol_val) {
+                    return Err((k.to_string(), ng, nt, dv));
+                }
+            }
+        }
+    }
+    Ok(())
+}
+
+/// Verifies `time_key` is monotonically non-decreasing across `events`,
+/// failing with the first offending index and the two offending timestamps.
+fn check_monotonic(events: &[Value], time_key: &str, label: &str) -> Result<(), String> {
+    let mut prev: Option<f64> = None;
+    for (i, e) in events.iter().enumerate() {
+        let t = get_f64(e, time_key).map_err(|_| format!("Missing/invalid {time_key} in {label} at idx={i}"))?;
+        if let Some(p) = prev {
+            if t < p {
+                return Err(format!(
+                    "Non-monotonic {time_key} in {label} at idx={i}: {time_key}[{prev_idx}]={p} > {time_key}[{i}]={t}",
+                    prev_idx = i - 1
+                ));
+            }
+        }
+        prev = Some(t);
+    }
+    Ok(())
+}
+
+pub struct MetaDiff {
+    pub gold_only: Vec<String>,
+    pub test_only: Vec<String>,
+    pub differing: Vec<(String, Value, Value)>,
+    pub gold_meta_lines: usize,
+    pub test_meta_lines: usize,
+}
+
+/// Compares only the meta lines of two UEC JSONL traces (reusing `parse_file`'s
+/// meta collection), ignoring events entirely. Meta lines within a file are
+/// merged field-by-field (later lines override earlier ones on key collision,
+/// `type` excluded), then the merged gold/test objects are diffed key-by-key.
+/// This is a fast config-drift check, independent of the (expensive) event
+/// comparison done by `run`.
+pub fn run_meta(gold: PathBuf, test: PathBuf) -> Result<MetaDiff, String> {
+    let (_gold_events, gold_meta, _gold_skips) = parse_file(&gold).map_err(|e| format!("Failed to read gold file: {e}"))?;
+    let (_test_events, test_meta, _test_skips) = parse_file(&test).map_err(|e| format!("Failed to read test file: {e}"))?;
+
+    let gold_fields = merge_meta_fields(&gold_meta);
+    let test_fields = merge_meta_fields(&test_meta);
+
+    let gold_keys: HashSet<_> = gold_fields.keys().collect();
+    let test_keys: HashSet<_> = test_fields.keys().collect();
+
+    let mut gold_only: Vec<String> = gold_keys.difference(&test_keys).map(|k| (*k).clone()).collect();
+    gold_only.sort();
+    let mut test_only: Vec<String> = test_keys.difference(&gold_keys).map(|k| (*k).clone()).collect();
+    test_only.sort();
+
+    let mut differing: Vec<(String, Value, Value)> = Vec::new();
+    let mut common: Vec<_> = gold_keys.intersection(&test_keys).map(|k| (*k).clone()).collect();
+    common.sort();
+    for k in common {
+        let gv = &gold_fields[&k];
+        let tv = &test_fields[&k];
+        if gv != tv {
+            differing.push((k.clone(), gv.clone(), tv.clone()));
+        }
+    }
+
+    Ok(MetaDiff {
+        gold_only,
+        test_only,
+        differing,
+        gold_meta_lines: gold_meta.len(),
+        test_meta_lines: test_meta.len(),
     })
 }
 
-fn parse_file(path: &Path) -> io::Result<(Vec<Value>, Vec<Value>)> {
+fn merge_meta_fields(meta_lines: &[Value]) -> HashMap<String, Value> {
+    let mut fields = HashMap::new();
+    for line in meta_lines {
+        if let Some(obj) = line.as_object() {
+            for (k, v) in obj {
+                if k == "type" {
+                    continue;
+                }
+                fields.insert(k.clone(), v.clone());
+            }
+        }
+    }
+    fields
+}
+
+/// Opens `path`, transparently decompressing it if it starts with the gzip
+/// magic bytes (`1f 8b`), regardless of extension — mirrors the detection
+/// eventflow-modules' `open_segment_reader` already does for native kernels,
+/// so a `.jsonl.gz` golden trace compares the same as a plain `.jsonl` one.
+fn open_reader(path: &Path) -> io::Result<BufReader<Box<dyn io::Read>>> {
     let file = File::open(path)?;
-    let reader = BufReader::new(file);
+    let mut buffered = BufReader::new(file);
+    let is_gzip = {
+        let peek = buffered.fill_buf()?;
+        peek.len() >= 2 && peek[0] == 0x1f && peek[1] == 0x8b
+    };
+    let inner: Box<dyn io::Read> = if is_gzip {
+        Box::new(flate2::read::GzDecoder::new(buffered))
+    } else {
+        Box::new(buffered)
+    };
+    Ok(BufReader::new(inner))
+}
+
+/// A line successfully classified by `classify_line`: either an event
+/// (`type == "event"` or `"deliver"`) or a meta line (`type == "meta"`).
+enum LineKind {
+    Event(Value),
+    Meta(Value),
+}
+
+/// Why `classify_line` rejected a line. `ef validate` reports each of these
+/// as a violation; `parse_file`'s tolerant collection just skips them.
+enum LineIssue {
+    InvalidJson,
+    MissingType,
+    UnknownType(String),
+}
+
+/// Parses one raw JSONL line and classifies it by its `type` field. Shared by
+/// `parse_file` (which silently skips anything this returns `Err` for) and
+/// `validate_file` (which reports every `Err` as a schema violation instead).
+fn classify_line(s: &str) -> Result<LineKind, LineIssue> {
+    let val: Value = serde_json::from_str(s).map_err(|_| LineIssue::InvalidJson)?;
+    match val.get("type").and_then(|v| v.as_str()) {
+        Some("meta") => Ok(LineKind::Meta(val)),
+        Some("event") | Some("deliver") => Ok(LineKind::Event(val)),
+        Some(other) => Err(LineIssue::UnknownType(other.to_string())),
+        None => Err(LineIssue::MissingType),
+    }
+}
+
+/// One line `parse_file` couldn't use, so a caller (e.g. `compare::run`) can
+/// report exactly which lines were silently dropped instead of a trace
+/// comparing "successfully" only because both sides happened to drop the
+/// same trailing garbage.
+#[derive(serde::Serialize, Clone)]
+pub struct ParseSkip {
+    pub line_no: usize,
+    pub reason: String,
+}
+
+fn line_issue_reason(issue: LineIssue) -> String {
+    match issue {
+        LineIssue::InvalidJson => "invalid JSON".to_string(),
+        LineIssue::MissingType => "missing \"type\" field".to_string(),
+        LineIssue::UnknownType(t) => format!("unknown type '{t}'"),
+    }
+}
+
+fn parse_file(path: &Path) -> io::Result<(Vec<Value>, Vec<Value>, Vec<ParseSkip>)> {
+    let reader = open_reader(path)?;
     let mut events = Vec::new();
     let mut metas = Vec::new();
+    let mut skipped = Vec::new();
 
-    for line in reader.lines() {
+    for (i, line) in reader.lines().enumerate() {
+        let line_no = i + 1;
         let line = match line {
             Ok(s) => s,
-            Err(_) => continue, // ignore unreadable line
+            Err(e) => {
+                skipped.push(ParseSkip { line_no, reason: format!("read error: {e}") });
+                continue;
+            }
         };
         let s = line.trim();
         if s.is_empty() {
             continue;
         }
-        let val: Value = match serde_json::from_str(s) {
-            Ok(v) => v,
-            Err(_) => continue, // treat unparseable as ignorable
+        match classify_line(s) {
+            Ok(LineKind::Event(v)) => events.push(v),
+            Ok(LineKind::Meta(v)) => metas.push(v),
+            Err(issue) => skipped.push(ParseSkip { line_no, reason: line_issue_reason(issue) }),
+        }
+    }
+    Ok((events, metas, skipped))
+}
+
+/// Lazily yields one event-bearing `Value` at a time from a JSONL file,
+/// without ever materializing the whole file as a `Vec`. Meta lines are
+/// counted (`meta_count`) and unparseable lines recorded (`skips`) as side
+/// effects of iteration rather than being collected up front, so a caller
+/// that only needs the default in-order comparison can hold at most one
+/// gold and one test event in memory at a time instead of the whole trace.
+struct EventLineStream {
+    reader: BufReader<Box<dyn io::Read>>,
+    line_no: usize,
+    meta_count: usize,
+    skips: Vec<ParseSkip>,
+}
+
+impl EventLineStream {
+    fn open(path: &Path) -> io::Result<Self> {
+        Ok(EventLineStream { reader: open_reader(path)?, line_no: 0, meta_count: 0, skips: Vec::new() })
+    }
+}
+
+impl Iterator for EventLineStream {
+    type Item = io::Result<Value>;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        loop {
+            let mut buf = String::new();
+            let n = match self.reader.read_line(&mut buf) {
+                Ok(n) => n,
+                Err(e) => return Some(Err(e)),
+            };
+            if n == 0 {
+                return None;
+            }
+            self.line_no += 1;
+            let s = buf.trim();
+            if s.is_empty() {
+                continue;
+            }
+            match classify_line(s) {
+                Ok(LineKind::Event(v)) => return Some(Ok(v)),
+                Ok(LineKind::Meta(_)) => {
+                    self.meta_count += 1;
+                    continue;
+                }
+                Err(issue) => {
+                    self.skips.push(ParseSkip { line_no: self.line_no, reason: line_issue_reason(issue) });
+                    continue;
+                }
+            }
+        }
+    }
+}
+
+/// Pulls the next event from `stream`, transparently skipping (and counting)
+/// any whose `time_key` is below `skip_before` — the streaming equivalent of
+/// the buffered path's `Vec::retain` pass, but one event at a time.
+fn next_filtered(
+    stream: &mut EventLineStream,
+    skip_before: Option<f64>,
+    time_key: &str,
+    skipped: &mut usize,
+) -> io::Result<Option<Value>> {
+    loop {
+        match stream.next() {
+            None => return Ok(None),
+            Some(Err(e)) => return Err(e),
+            Some(Ok(v)) => {
+                if let Some(sb) = skip_before {
+                    if get_f64(&v, time_key).map(|t| t < sb).unwrap_or(false) {
+                        *skipped += 1;
+                        continue;
+                    }
+                }
+                return Ok(Some(v));
+            }
+        }
+    }
+}
+
+/// Streams the default in-order comparison directly off disk via
+/// `EventLineStream`, holding at most one gold and one test event (plus the
+/// running aggregates every mode already keeps) in memory at a time instead
+/// of materializing either file as a `Vec<Value>`. Only the default
+/// positional path can work this way: `--unordered` and `--match-by-key`
+/// both need the full candidate list to pick a match, and
+/// `--auto-value-offset` needs every pair's delta before it can commit to an
+/// offset, so `run_with_progress` falls back to the buffered path for those.
+///
+/// Because lengths are no longer known ahead of time, a length mismatch is
+/// reported at the point one side runs out before the other (rather than as
+/// a pair of totals up front), and `Summary::events`/`CompareError::Mismatch
+/// { events, .. }` count pairs actually compared rather than the full file
+/// length when a non-`--per-type` run stops early at `--max-errors`.
+#[allow(clippy::result_large_err)]
+fn run_ordered_streaming(
+    opts: &CompareOptions,
+    progress_every: usize,
+    on_progress: &mut dyn FnMut(usize, usize),
+) -> Result<Summary, CompareError> {
+    let mut gold = EventLineStream::open(&opts.gold)
+        .map_err(|e| CompareError::Fatal(format!("Failed to read gold file: {e}")))?;
+    let mut test = EventLineStream::open(&opts.test)
+        .map_err(|e| CompareError::Fatal(format!("Failed to read test file: {e}")))?;
+
+    let time_key = opts.time_key.as_str();
+    let mut skipped_gold = 0usize;
+    let mut skipped_test = 0usize;
+    let mut max_time_delta = 0.0f64;
+    let mut max_value_deltas: HashMap<String, f64> = HashMap::new();
+    let mut type_counts: HashMap<String, (usize, usize)> = HashMap::new();
+    let mut mismatches: Vec<Mismatch> = Vec::new();
+    let mut matched_so_far = 0usize;
+    let mut events_compared = 0usize;
+    let mut prev_gold_t: Option<f64> = None;
+    let mut prev_test_t: Option<f64> = None;
+
+    loop {
+        let g = next_filtered(&mut gold, opts.skip_before, time_key, &mut skipped_gold)
+            .map_err(|e| CompareError::Fatal(format!("Failed to read gold file: {e}")))?;
+        let t = next_filtered(&mut test, opts.skip_before, time_key, &mut skipped_test)
+            .map_err(|e| CompareError::Fatal(format!("Failed to read test file: {e}")))?;
+
+        let (g, t) = match (g, t) {
+            (None, None) => break,
+            (Some(_), None) => {
+                return Err(CompareError::Fatal(format!(
+                    "Event length mismatch: gold has more events than test (test exhausted after idx={events_compared})"
+                )));
+            }
+            (None, Some(_)) => {
+                return Err(CompareError::Fatal(format!(
+                    "Event length mismatch: test has more events than gold (gold exhausted after idx={events_compared})"
+                )));
+            }
+            (Some(g), Some(t)) => (g, t),
+        };
+
+        let i = events_compared;
+        events_compared += 1;
+
+        let kind_g = g.get("type").and_then(|v| v.as_str()).unwrap_or("?");
+        let kind_t = t.get("type").and_then(|v| v.as_str()).unwrap_or("?");
+
+        let tg = get_f64(&g, time_key).map_err(|_| CompareError::Fatal(format!("Missing/invalid {time_key} in gold at idx={i}")))?;
+        let tt = get_f64(&t, time_key).map_err(|_| CompareError::Fatal(format!("Missing/invalid {time_key} in test at idx={i}")))?;
+
+        if opts.require_monotonic {
+            if let Some(p) = prev_gold_t {
+                if tg < p {
+                    return Err(CompareError::Fatal(format!(
+                        "Non-monotonic {time_key} in gold at idx={i}: {time_key}[{prev_idx}]={p} > {time_key}[{i}]={tg}",
+                        prev_idx = i - 1
+                    )));
+                }
+            }
+            prev_gold_t = Some(tg);
+            if let Some(p) = prev_test_t {
+                if tt < p {
+                    return Err(CompareError::Fatal(format!(
+                        "Non-monotonic {time_key} in test at idx={i}: {time_key}[{prev_idx}]={p} > {time_key}[{i}]={tt}",
+                        prev_idx = i - 1
+                    )));
+                }
+            }
+            prev_test_t = Some(tt);
+        }
+
+        if kind_g != kind_t {
+            let stop = record_outcome(
+                opts,
+                i,
+                kind_g,
+                false,
+                format!("Mismatch at idx={i}: kind gold='{kind_g}' test='{kind_t}'"),
+                None,
+                HashMap::new(),
+                &mut type_counts,
+                &mut mismatches,
+            );
+            if progress_every != 0 && (i + 1).is_multiple_of(progress_every) {
+                on_progress(i, matched_so_far);
+            }
+            if stop {
+                break;
+            }
+            continue;
+        }
+
+        let dt = (tg - tt).abs();
+        if dt > max_time_delta {
+            max_time_delta = dt;
+        }
+        if !within_tolerance(dt, tg, tt, opts.tol_time, opts.rel_tol_time) {
+            let stop = record_outcome(
+                opts,
+                i,
+                kind_g,
+                false,
+                format!(
+                    "Time mismatch at idx={i}: kind={kind_g} {time_key}_gold={tg} {time_key}_test={tt} Δt={dt} > tol_time={}",
+                    opts.tol_time
+                ),
+                Some(dt),
+                HashMap::new(),
+                &mut type_counts,
+                &mut mismatches,
+            );
+            if progress_every != 0 && (i + 1).is_multiple_of(progress_every) {
+                on_progress(i, matched_so_far);
+            }
+            if stop {
+                break;
+            }
+            continue;
+        }
+
+        let stop = match check_payload(&g, &t, opts, &mut max_value_deltas) {
+            Ok(()) => {
+                matched_so_far += 1;
+                record_outcome(
+                    opts, i, kind_g, true, String::new(), None, HashMap::new(), &mut type_counts, &mut mismatches,
+                )
+            }
+            Err((k, ng, nt, dv)) => record_outcome(
+                opts,
+                i,
+                kind_g,
+                false,
+                format!(
+                    "Payload numeric mismatch at idx={i}: kind={kind_g} key='{k}' gold={ng} test={nt} Δ={dv} > tol_val={}",
+                    opts.tol_val
+                ),
+                None,
+                HashMap::from([(k, dv)]),
+                &mut type_counts,
+                &mut mismatches,
+            ),
+        };
+        if progress_every != 0 && (i + 1).is_multiple_of(progress_every) {
+            on_progress(i, matched_so_far);
+        }
+        if stop {
+            break;
+        }
+    }
+
+    if !mismatches.is_empty() {
+        return Err(CompareError::Mismatch {
+            events: events_compared,
+            meta_gold: gold.meta_count,
+            meta_test: test.meta_count,
+            tol_time: opts.tol_time,
+            tol_val: opts.tol_val,
+            mismatches,
+            type_counts,
+            gold_parse_skips: gold.skips,
+            test_parse_skips: test.skips,
+            value_offsets_used: opts.value_offset_keys.clone(),
+        });
+    }
+
+    Ok(Summary {
+        events: events_compared,
+        meta_gold: gold.meta_count,
+        meta_test: test.meta_count,
+        tol_time: opts.tol_time,
+        tol_val: opts.tol_val,
+        rel_tol_time: opts.rel_tol_time,
+        rel_tol_val: opts.rel_tol_val,
+        skipped_gold,
+        skipped_test,
+        max_time_delta,
+        max_value_deltas,
+        type_counts,
+        gold_parse_skips: gold.skips,
+        test_parse_skips: test.skips,
+        value_offsets_used: opts.value_offset_keys.clone(),
+    })
+}
+
+/// One schema violation found by `validate_file`, 1-indexed by line number
+/// within the file (matching how editors and `grep -n` report line numbers).
+#[derive(serde::Serialize)]
+pub struct Violation {
+    pub line_no: usize,
+    pub kind: String,
+    pub detail: String,
+}
+
+/// Result of `validate_file`: every line scanned plus every violation found,
+/// so `ef validate` can report both a total and a targeted list.
+#[derive(serde::Serialize)]
+pub struct ValidationReport {
+    pub lines: usize,
+    pub violations: Vec<Violation>,
+}
+
+/// Streams `path` line-by-line via the same `classify_line` `parse_file`
+/// uses, but reports every line it can't use instead of silently skipping
+/// it: invalid JSON, a missing `type`, a `type` outside {event, deliver,
+/// meta}, or (for event/deliver lines) a missing/unparseable `t_s`. Blank
+/// lines are not counted or flagged, matching `parse_file`'s treatment of
+/// them as formatting, not content.
+pub fn validate_file(path: &Path) -> io::Result<ValidationReport> {
+    let reader = open_reader(path)?;
+    let mut lines = 0usize;
+    let mut violations = Vec::new();
+
+    for (i, line) in reader.lines().enumerate() {
+        let line_no = i + 1;
+        let line = match line {
+            Ok(s) => s,
+            Err(e) => {
+                violations.push(Violation {
+                    line_no,
+                    kind: "io_error".to_string(),
+                    detail: e.to_string(),
+                });
+                continue;
+            }
         };
-        match val.get("type").and_then(|v| v.as_str()) {
-            Some("meta") => metas.push(val),
-            Some("event") | Some("deliver") => events.push(val),
-            _ => { /* ignore other types */ }
+        let s = line.trim();
+        if s.is_empty() {
+            continue;
+        }
+        lines += 1;
+        match classify_line(s) {
+            Ok(LineKind::Meta(_)) => {}
+            Ok(LineKind::Event(v)) => {
+                if get_f64(&v, "t_s").is_err() {
+                    violations.push(Violation {
+                        line_no,
+                        kind: "missing_t_s".to_string(),
+                        detail: "event/deliver line has no numeric/parseable \"t_s\"".to_string(),
+                    });
+                }
+            }
+            Err(LineIssue::InvalidJson) => violations.push(Violation {
+                line_no,
+                kind: "invalid_json".to_string(),
+                detail: "line is not valid JSON".to_string(),
+            }),
+            Err(LineIssue::MissingType) => violations.push(Violation {
+                line_no,
+                kind: "missing_type".to_string(),
+                detail: "line has no \"type\" field".to_string(),
+            }),
+            Err(LineIssue::UnknownType(t)) => violations.push(Violation {
+                line_no,
+                kind: "unknown_type".to_string(),
+                detail: format!("type '{t}' is not one of event, deliver, meta"),
+            }),
         }
     }
-    Ok((events, metas))
+    Ok(ValidationReport { lines, violations })
 }
 
 fn get_f64(v: &Value, key: &str) -> Result<f64, ()> {
@@ -134,4 +1367,281 @@ fn get_f64(v: &Value, key: &str) -> Result<f64, ()> {
         }
     }
     Err(())
+}
+
+/// Min/max/mean of one numeric payload key across every event that carried it.
+#[derive(serde::Serialize)]
+pub struct NumericKeyStats {
+    pub min: f64,
+    pub max: f64,
+    pub mean: f64,
+    pub count: usize,
+}
+
+/// Single-trace summary for `ef stats`: counts, per-`type` breakdown, the
+/// observed `time_key` range, and per-key numeric payload stats.
+#[derive(serde::Serialize)]
+pub struct TraceStats {
+    pub events: usize,
+    pub metas: usize,
+    pub type_counts: HashMap<String, usize>,
+    pub t_min: Option<f64>,
+    pub t_max: Option<f64>,
+    pub t_span: Option<f64>,
+    pub numeric_keys: HashMap<String, NumericKeyStats>,
+}
+
+/// Profiles a single UEC JSONL trace (reusing `parse_file`), without needing
+/// a second file to compare against: event/meta counts, a breakdown by the
+/// `type` field among event lines, the min/max/span of `time_key` across
+/// events, and min/max/mean for each numeric payload key observed.
+pub fn run_stats(path: &Path, time_key: &str) -> Result<TraceStats, String> {
+    let (events, metas, _skips) = parse_file(path).map_err(|e| format!("Failed to read file: {e}"))?;
+
+    let mut type_counts: HashMap<String, usize> = HashMap::new();
+    let mut t_min: Option<f64> = None;
+    let mut t_max: Option<f64> = None;
+    let mut sums: HashMap<String, f64> = HashMap::new();
+    let mut mins: HashMap<String, f64> = HashMap::new();
+    let mut maxs: HashMap<String, f64> = HashMap::new();
+    let mut counts: HashMap<String, usize> = HashMap::new();
+
+    for e in &events {
+        if let Some(kind) = e.get("type").and_then(|v| v.as_str()) {
+            *type_counts.entry(kind.to_string()).or_insert(0) += 1;
+        }
+        if let Ok(t) = get_f64(e, time_key) {
+            t_min = Some(t_min.map_or(t, |m| m.min(t)));
+            t_max = Some(t_max.map_or(t, |m| m.max(t)));
+        }
+        if let Some(payload) = e.get("payload").and_then(|v| v.as_object()) {
+            for (k, v) in payload {
+                let Some(n) = v.as_f64() else { continue };
+                if !n.is_finite() {
+                    continue;
+                }
+                *sums.entry(k.clone()).or_insert(0.0) += n;
+                *counts.entry(k.clone()).or_insert(0) += 1;
+                mins.entry(k.clone()).and_modify(|m| *m = n.min(*m)).or_insert(n);
+                maxs.entry(k.clone()).and_modify(|m| *m = n.max(*m)).or_insert(n);
+            }
+        }
+    }
+
+    let numeric_keys = counts
+        .into_iter()
+        .map(|(k, n)| {
+            let stats = NumericKeyStats {
+                min: mins[&k],
+                max: maxs[&k],
+                mean: sums[&k] / n as f64,
+                count: n,
+            };
+            (k, stats)
+        })
+        .collect();
+
+    let t_span = match (t_min, t_max) {
+        (Some(a), Some(b)) => Some(b - a),
+        _ => None,
+    };
+
+    Ok(TraceStats {
+        events: events.len(),
+        metas: metas.len(),
+        type_counts,
+        t_min,
+        t_max,
+        t_span,
+        numeric_keys,
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::sync::atomic::{AtomicUsize, Ordering};
+
+    static TMP_COUNTER: AtomicUsize = AtomicUsize::new(0);
+
+    /// Writes `lines` (already-serialized JSONL lines, no trailing newline) to
+    /// a fresh file under the OS temp dir and returns its path. Each call gets
+    /// a unique name so parallel `cargo test` runs never collide.
+    fn write_trace(name: &str, lines: &[&str]) -> PathBuf {
+        let n = TMP_COUNTER.fetch_add(1, Ordering::Relaxed);
+        let path = std::env::temp_dir().join(format!("ef-compare-test-{}-{}-{}.jsonl", std::process::id(), n, name));
+        let mut body = lines.join("\n");
+        body.push('\n');
+        std::fs::write(&path, body).expect("write temp trace");
+        path
+    }
+
+    fn default_opts(gold: PathBuf, test: PathBuf) -> CompareOptions {
+        CompareOptions {
+            gold,
+            test,
+            tol_time: 1e-6,
+            tol_val: 1e-6,
+            skip_before: None,
+            time_key: "t_s".to_string(),
+            unordered: false,
+            match_tiebreak: "first".to_string(),
+            require_monotonic: false,
+            per_type: false,
+            ignore_keys: HashSet::new(),
+            only_keys: None,
+            rel_tol_time: None,
+            rel_tol_val: None,
+            max_errors: 1,
+            match_by_key: false,
+            tolerance_keys: HashMap::new(),
+            value_offset_keys: HashMap::new(),
+            auto_value_offset_keys: HashSet::new(),
+        }
+    }
+
+    #[test]
+    fn ordered_streaming_matches_identical_traces() {
+        let gold = write_trace(
+            "identical-gold",
+            &[
+                r#"{"type":"event","t_s":1.0,"payload":{"val":1.0}}"#,
+                r#"{"type":"event","t_s":2.0,"payload":{"val":2.0}}"#,
+            ],
+        );
+        let test = write_trace(
+            "identical-test",
+            &[
+                r#"{"type":"event","t_s":1.0,"payload":{"val":1.0}}"#,
+                r#"{"type":"event","t_s":2.0,"payload":{"val":2.0}}"#,
+            ],
+        );
+        let summary = match run(default_opts(gold, test)) {
+            Ok(summary) => summary,
+            Err(CompareError::Fatal(msg)) => panic!("expected a clean match, got Fatal({msg})"),
+            Err(CompareError::Mismatch { .. }) => panic!("expected a clean match, got Mismatch"),
+        };
+        assert_eq!(summary.events, 2);
+        assert_eq!(summary.max_time_delta, 0.0);
+    }
+
+    #[test]
+    fn ordered_streaming_reports_time_mismatch() {
+        let gold = write_trace("time-mismatch-gold", &[r#"{"type":"event","t_s":1.0,"payload":{"val":1.0}}"#]);
+        let test = write_trace("time-mismatch-test", &[r#"{"type":"event","t_s":1.5,"payload":{"val":1.0}}"#]);
+        match run(default_opts(gold, test)) {
+            Err(CompareError::Mismatch { mismatches, .. }) => {
+                assert_eq!(mismatches.len(), 1);
+                assert!(mismatches[0].reason.contains("Time mismatch"));
+            }
+            Ok(_) => panic!("expected a time mismatch, got Ok"),
+            Err(CompareError::Fatal(msg)) => panic!("expected a time mismatch, got Fatal({msg})"),
+        }
+    }
+
+    #[test]
+    fn ordered_streaming_reports_value_mismatch() {
+        let gold = write_trace("value-mismatch-gold", &[r#"{"type":"event","t_s":1.0,"payload":{"val":1.0}}"#]);
+        let test = write_trace("value-mismatch-test", &[r#"{"type":"event","t_s":1.0,"payload":{"val":9.0}}"#]);
+        match run(default_opts(gold, test)) {
+            Err(CompareError::Mismatch { mismatches, .. }) => {
+                assert_eq!(mismatches.len(), 1);
+                assert!(mismatches[0].reason.contains("Payload numeric mismatch"));
+            }
+            Ok(_) => panic!("expected a value mismatch, got Ok"),
+            Err(CompareError::Fatal(msg)) => panic!("expected a value mismatch, got Fatal({msg})"),
+        }
+    }
+
+    /// Regression test for the streaming `require_monotonic` bug: a kind
+    /// mismatch used to `continue` before `tg`/`tt` were parsed and before
+    /// `prev_gold_t`/`prev_test_t` were updated, so a gold timestamp
+    /// regression spanning a kind-mismatched event went undetected.
+    #[test]
+    fn ordered_streaming_require_monotonic_catches_regression_across_kind_mismatch() {
+        let gold = write_trace(
+            "monotonic-gold",
+            &[
+                r#"{"type":"event","t_s":1.0}"#,
+                r#"{"type":"deliver","t_s":5.0}"#,
+                r#"{"type":"event","t_s":3.0}"#,
+            ],
+        );
+        let test = write_trace(
+            "monotonic-test",
+            &[
+                r#"{"type":"event","t_s":1.0}"#,
+                r#"{"type":"event","t_s":2.0}"#,
+                r#"{"type":"event","t_s":3.0}"#,
+            ],
+        );
+        let mut opts = default_opts(gold, test);
+        opts.require_monotonic = true;
+        opts.max_errors = 0;
+        match run(opts) {
+            Err(CompareError::Fatal(msg)) => {
+                assert!(msg.contains("Non-monotonic"), "unexpected error: {msg}");
+                assert!(msg.contains("gold"), "expected the gold stream to be flagged: {msg}");
+            }
+            Ok(_) => panic!("expected a non-monotonic gold error, got Ok"),
+            Err(CompareError::Mismatch { .. }) => panic!("expected a Fatal non-monotonic error, got Mismatch"),
+        }
+    }
+
+    #[test]
+    fn run_meta_reports_differing_and_exclusive_fields() {
+        let gold = write_trace(
+            "meta-gold",
+            &[
+                r#"{"type":"meta","seed":1,"gold_only":"yes"}"#,
+                r#"{"type":"event","t_s":1.0}"#,
+            ],
+        );
+        let test = write_trace(
+            "meta-test",
+            &[
+                r#"{"type":"meta","seed":2,"test_only":"yes"}"#,
+                r#"{"type":"event","t_s":1.0}"#,
+            ],
+        );
+        let diff = run_meta(gold, test).expect("run_meta should succeed");
+        assert_eq!(diff.gold_only, vec!["gold_only".to_string()]);
+        assert_eq!(diff.test_only, vec!["test_only".to_string()]);
+        assert_eq!(diff.differing.len(), 1);
+        assert_eq!(diff.differing[0].0, "seed");
+    }
+
+    #[test]
+    fn run_stats_computes_span_and_type_counts() {
+        let path = write_trace(
+            "stats",
+            &[
+                r#"{"type":"event","t_s":1.0,"payload":{"val":1.0}}"#,
+                r#"{"type":"event","t_s":4.0,"payload":{"val":2.0}}"#,
+            ],
+        );
+        let stats = run_stats(&path, "t_s").expect("run_stats should succeed");
+        assert_eq!(stats.events, 2);
+        assert_eq!(stats.t_min, Some(1.0));
+        assert_eq!(stats.t_max, Some(4.0));
+        assert_eq!(stats.t_span, Some(3.0));
+        assert_eq!(stats.type_counts.get("event"), Some(&2));
+    }
+
+    #[test]
+    fn validate_file_flags_missing_and_unknown_type() {
+        let path = write_trace(
+            "validate",
+            &[
+                r#"{"type":"event","t_s":1.0}"#,
+                r#"{"no_type":true}"#,
+                r#"{"type":"bogus"}"#,
+            ],
+        );
+        let report = validate_file(&path).expect("validate_file should succeed");
+        assert_eq!(report.lines, 3);
+        assert_eq!(report.violations.len(), 2);
+        assert_eq!(report.violations[0].kind, "missing_type");
+        assert_eq!(report.violations[1].kind, "unknown_type");
+    }
 }
\ No newline at end of file
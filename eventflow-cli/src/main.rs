@@ -1,6 +1,6 @@
 mod compare;
 
-use compare::{run, CompareOptions};
+use compare::{run, run_meta, run_stats, validate_file, CompareOptions};
 use std::env;
 use std::path::PathBuf;
 use std::process::ExitCode;
@@ -12,41 +12,255 @@ Usage:
   ef <subcommand> [options]
 
 Subcommands:
-  compare    Compare two UEC JSONL traces with time/value tolerances.
+  compare       Compare two UEC JSONL traces with time/value tolerances.
+  compare-meta  Diff only the meta lines of two UEC JSONL traces (config drift).
+  stats         Summarize a single UEC JSONL trace (counts, types, value ranges).
+  validate      Check a single UEC JSONL trace for schema conformance.
 
 Run:
   ef compare --help
+  ef compare-meta --help
+  ef stats --help
+  ef validate --help
 for detailed options and examples."
     );
 }
 
+fn print_compare_meta_help() {
+    println!(
+        "ef compare-meta --gold <gold.jsonl> --test <test.jsonl>
+
+Options:
+  --gold PATH   Path to golden/reference UEC JSONL file.
+  --test PATH   Path to candidate/test UEC JSONL file.
+
+Behavior:
+  - Parses both files line-by-line via the same reader compare uses, but only
+    keeps lines with type == \"meta\"; events are ignored entirely.
+  - Merges each file's meta lines into one field set (later lines override
+    earlier ones on key collision, the \"type\" field itself excluded), then
+    diffs the two merged field sets key-by-key.
+  - Reports keys present in only one file and keys present in both with
+    differing values. Exits 0 if the field sets are identical, 1 otherwise.
+
+Example:
+  ef compare-meta --gold out/golden.jsonl --test out/candidate.jsonl"
+    );
+}
+
+fn print_stats_help() {
+    println!(
+        "ef stats --input <trace.jsonl>
+
+Options:
+  --input PATH    Path to a UEC JSONL trace. Gzip compression is
+                  auto-detected (magic bytes, not extension), same as
+                  --gold/--test in ef compare.
+  --time-key NAME Field name holding the timestamp (default: t_s).
+  --format text|json  Output format (default: text). With json, prints a
+                  single-line object with fields events, metas, type_counts,
+                  t_min, t_max, t_span, numeric_keys (each key mapped to
+                  min/max/mean/count).
+
+Behavior:
+  - Parses the file via the same reader compare uses (events with
+    type == \"event\" or \"deliver\", metas with type == \"meta\").
+  - Reports event/meta counts and a breakdown by the \"type\" field among
+    events.
+  - Reports min/max/span of time-key across events.
+  - For each numeric payload key observed on any event, reports min/max/mean
+    across all events that carried it.
+
+Example:
+  ef stats --input out/golden.jsonl
+  ef stats --input out/golden.jsonl.gz --format json"
+    );
+}
+
+fn print_validate_help() {
+    println!(
+        "ef validate --input <trace.jsonl>
+
+Options:
+  --input PATH    Path to a UEC JSONL trace. Gzip compression is
+                  auto-detected (magic bytes, not extension), same as
+                  --gold/--test in ef compare.
+  --max-report N  Print at most N offending line numbers (default: 20).
+                  The reported count and, with --format json, the full
+                  violation list are never truncated by this — it only
+                  limits the human-readable line-number listing.
+  --format text|json  Output format (default: text). With json, prints a
+                  single-line object with fields lines, violation_count,
+                  and violations (each with line_no, kind, detail).
+
+Behavior:
+  - Streams the file line-by-line via the same classifier ef compare's
+    parse_file uses, but reports every line it can't use instead of
+    silently skipping it:
+      invalid_json   line is not valid JSON
+      missing_type   line has no \"type\" field
+      unknown_type   \"type\" is not one of event, deliver, meta
+      missing_t_s    an event/deliver line has no numeric/parseable \"t_s\"
+  - Blank lines are not counted or flagged.
+  - Exits 0 if no violations were found, 1 otherwise.
+
+Example:
+  ef validate --input out/candidate.jsonl
+  ef validate --input out/candidate.jsonl --format json"
+    );
+}
+
 fn print_compare_help() {
     println!(
         "ef compare --gold <gold.jsonl> --test <test.jsonl> --tolerance-time <seconds f64> --tolerance-val <f64>
 
 Options:
-  --gold PATH             Path to golden/reference UEC JSONL file.
-  --test PATH             Path to candidate/test UEC JSONL file.
+  --gold PATH             Path to golden/reference UEC JSONL file. Gzip
+                           compression is auto-detected (magic bytes, not
+                           extension), so a plain .jsonl or a .jsonl.gz both
+                           work unchanged.
+  --test PATH             Path to candidate/test UEC JSONL file. Same
+                           gzip auto-detection as --gold, independently.
   --tolerance-time F64    Allowed absolute timestamp delta in seconds.
   --tolerance-val F64     Allowed absolute numeric payload delta.
+  --skip-before F64       Drop events with t_s less than this value from both
+                           files (after parsing, before the length check).
+  --time-key NAME         Field name holding the timestamp to compare
+                           (default: t_s). Use for trace dialects that store
+                           the timestamp under a different key, e.g. \"time\".
+  --unordered             Match gold and test events by nearest-in-tolerance
+                           timestamp instead of requiring identical order.
+                           Lengths must still match.
+  --match-by-key          Group events by (type, tol_time-wide timestamp
+                           bucket) and compare each bucket's gold/test
+                           events as a multiset instead of positionally, so
+                           equal-timestamp events emitted in a different but
+                           still-correct relative order don't produce false
+                           mismatches. A bucket whose gold/test counts
+                           differ is reported as one mismatch naming the
+                           bucket time and the surplus/missing count.
+                           Takes precedence over --unordered if both are
+                           given. Lengths must still match.
+  --match-tiebreak NAME   Tie-breaking rule used when multiple unconsumed
+                           test events fall within --tolerance-time of a gold
+                           event (only meaningful with --unordered):
+                             nearest-time  smallest |Δt|                (default)
+                             first         lowest original index
+                             best-value    smallest summed numeric payload Δ,
+                                           ties broken by nearest-time
+  --require-monotonic     Before comparing, verify time-key is non-decreasing
+                           within each of gold and test; fails with the first
+                           offending index and the two offending timestamps.
+  --per-type              Tally matched/mismatched counts grouped by the
+                           \"type\" field instead of failing at the first
+                           mismatch, then print a per-type breakdown at the
+                           end. The run still fails overall if any mismatches
+                           were tallied.
+  --ignore-keys K1,K2     Exclude these payload keys from the value
+                           comparison entirely (blacklist). Comma-separated.
+  --ignore-key KEY        Single-key, repeatable form of --ignore-keys; adds
+                           to the same blacklist. Use whichever reads better
+                           at the call site.
+  --only-keys K1,K2       Compare only these payload keys (whitelist),
+                           ignoring any other numeric fields present on both
+                           sides. Comma-separated. Applied before
+                           --ignore-keys, so a key must survive both.
+  --tolerance-key K=F64   Override --tolerance-val for one payload key.
+                           Repeatable. Keys not given here keep using the
+                           global --tolerance-val; a key that never appears
+                           in either payload is simply never consulted.
+  --value-offset-key K=OFFSET  Subtract OFFSET from the test value of payload
+                           key K before the tolerance check. Repeatable. For
+                           a known, constant calibration bias on one field.
+  --auto-value-offset K   Estimate K's constant bias as the median
+                           (test - gold) difference across gold[i]/test[i]
+                           pairs, then apply it like --value-offset-key.
+                           Repeatable. The offset(s) used are reported in
+                           the output. If --value-offset-key is also given
+                           for the same key, the estimated value wins.
+  --rel-tolerance-time F64 Relative tolerance for the time-key delta, checked
+                           as |g-t| <= rel * max(|g|,|t|). A pair passes if
+                           either the absolute or the relative check passes.
+  --rel-tolerance-val F64  Relative tolerance for numeric payload deltas,
+                           same either-check-passes rule as above. Useful
+                           when payload magnitudes span several orders of
+                           magnitude and a single absolute tol_val is either
+                           too strict near small values or too loose near
+                           large ones.
+  --max-errors N          Collect up to N mismatch descriptions before
+                           stopping the scan, instead of failing at the
+                           first one (default: 1, preserving the original
+                           behavior). 0 means unlimited: scan to the end and
+                           report every mismatch found. The exit code is
+                           still non-zero whenever at least one mismatch was
+                           found.
+  --format text|json      Output format (default: text). With json, prints
+                           a single-line result object with fields matched,
+                           meta_gold, meta_test, tol_time, tol_val, status
+                           (\"ok\"/\"mismatch\"), and mismatches (each with
+                           idx, kind, reason, time_delta, value_deltas) —
+                           for CI dashboards that parse results
+                           programmatically instead of scraping the
+                           OK:/COMPARE MISMATCH: text.
 
 Behavior:
   - Parses both files line-by-line; collects event-bearing lines (type == \"event\" or \"deliver\").
   - Skips lines with type == \"meta\" (non-fatal; reported as counts).
-  - Compares event sequences in-order; lengths must match.
+  - With --require-monotonic, rejects either file whose time-key decreases
+    between consecutive events before any other comparison runs, catching a
+    real export bug instead of silently comparing an out-of-order file.
+  - By default, compares event sequences in-order; lengths must match.
+  - With --per-type, scans the whole comparison instead of stopping at the
+    first mismatch, so the printed breakdown can localize a regression to a
+    specific event type (e.g. \"deliver\" diverges while \"event\" matches).
+  - With --unordered, for each gold event (in order) scans unconsumed test
+    events of the same type within tolerance-time and selects one via
+    --match-tiebreak, so the match is deterministic and reproducible even
+    when events arrive out of order.
+  - With --match-by-key, gold and test events are grouped into (type,
+    tolerance-time-wide bucket) keys and compared bucket-by-bucket as
+    multisets, so tied timestamps emitted in a different relative order
+    don't produce false mismatches.
   - For each pair, checks |t_s_gold - t_s_test| ≤ tolerance-time.
   - If both have a payload with numeric fields, checks |gold - test| ≤ tolerance-val per numeric key (optional if missing).
+  - On success, also reports the worst observed time delta and per-key value delta across
+    the whole comparison, so drift can be tracked even while still within tolerance.
 
 Example:
-  ef compare --gold out/golden.jsonl --test out/candidate.jsonl --tolerance-time 1e-6 --tolerance-val 1e-5"
+  ef compare --gold out/golden.jsonl --test out/candidate.jsonl --tolerance-time 1e-6 --tolerance-val 1e-5 --skip-before 0.005
+  ef compare --gold out/golden.jsonl --test out/candidate.jsonl --tolerance-time 1e-6 --tolerance-val 1e-5 --unordered --match-tiebreak best-value
+  ef compare --gold out/golden.jsonl --test out/candidate.jsonl --tolerance-time 1e-6 --tolerance-val 1e-5 --per-type
+  ef compare --gold out/golden.jsonl --test out/candidate.jsonl --tolerance-time 1e-6 --tolerance-val 1e-5 --ignore-keys jitter_id,seq
+  ef compare --gold out/golden.jsonl --test out/candidate.jsonl --tolerance-time 1e-6 --tolerance-val 1e-5 --rel-tolerance-val 1e-3
+  ef compare --gold out/golden.jsonl --test out/candidate.jsonl --tolerance-time 1e-6 --tolerance-val 1e-5 --max-errors 0
+  ef compare --gold out/golden.jsonl --test out/candidate.jsonl --tolerance-time 1e-6 --tolerance-val 1e-5 --format json
+  ef compare --gold out/golden.jsonl --test out/candidate.jsonl --tolerance-time 1e-6 --tolerance-val 1e-5 --match-by-key
+  ef compare --gold out/golden.jsonl --test out/candidate.jsonl --tolerance-time 1e-6 --tolerance-val 1e-5 --tolerance-key count=1.0 --tolerance-key seq=0
+  ef compare --gold out/golden.jsonl --test out/candidate.jsonl --tolerance-time 1e-6 --tolerance-val 1e-5 --auto-value-offset temp"
     );
 }
 
-fn parse_compare_args(mut args: impl Iterator<Item = String>) -> Result<CompareOptions, String> {
+fn parse_compare_args(mut args: impl Iterator<Item = String>) -> Result<(CompareOptions, String), String> {
     let mut gold: Option<PathBuf> = None;
     let mut test: Option<PathBuf> = None;
     let mut tol_time: Option<f64> = None;
     let mut tol_val: Option<f64> = None;
+    let mut skip_before: Option<f64> = None;
+    let mut time_key: String = "t_s".to_string();
+    let mut unordered = false;
+    let mut match_tiebreak: String = "nearest-time".to_string();
+    let mut require_monotonic = false;
+    let mut per_type = false;
+    let mut ignore_keys: std::collections::HashSet<String> = std::collections::HashSet::new();
+    let mut only_keys: Option<std::collections::HashSet<String>> = None;
+    let mut rel_tol_time: Option<f64> = None;
+    let mut rel_tol_val: Option<f64> = None;
+    let mut max_errors: usize = 1;
+    let mut format: String = "text".to_string();
+    let mut match_by_key = false;
+    let mut tolerance_keys: std::collections::HashMap<String, f64> = std::collections::HashMap::new();
+    let mut value_offset_keys: std::collections::HashMap<String, f64> = std::collections::HashMap::new();
+    let mut auto_value_offset_keys: std::collections::HashSet<String> = std::collections::HashSet::new();
 
     while let Some(arg) = args.next() {
         match arg.as_str() {
@@ -76,6 +290,100 @@ fn parse_compare_args(mut args: impl Iterator<Item = String>) -> Result<CompareO
                     .ok_or_else(|| "Expected value after --tolerance-val".to_string())?;
                 tol_val = Some(v.parse::<f64>().map_err(|_| "Invalid float for --tolerance-val".to_string())?);
             }
+            "--skip-before" => {
+                let v = args
+                    .next()
+                    .ok_or_else(|| "Expected value after --skip-before".to_string())?;
+                skip_before = Some(v.parse::<f64>().map_err(|_| "Invalid float for --skip-before".to_string())?);
+            }
+            "--time-key" => {
+                time_key = args.next().ok_or_else(|| "Expected value after --time-key".to_string())?;
+            }
+            "--unordered" => {
+                unordered = true;
+            }
+            "--match-by-key" => {
+                match_by_key = true;
+            }
+            "--match-tiebreak" => {
+                let v = args
+                    .next()
+                    .ok_or_else(|| "Expected value after --match-tiebreak".to_string())?;
+                if v != "nearest-time" && v != "first" && v != "best-value" {
+                    return Err(format!(
+                        "Invalid --match-tiebreak '{v}' (expected nearest-time, first, or best-value)"
+                    ));
+                }
+                match_tiebreak = v;
+            }
+            "--require-monotonic" => {
+                require_monotonic = true;
+            }
+            "--per-type" => {
+                per_type = true;
+            }
+            "--ignore-keys" => {
+                let v = args.next().ok_or_else(|| "Expected value after --ignore-keys".to_string())?;
+                ignore_keys.extend(v.split(',').map(|s| s.trim().to_string()).filter(|s| !s.is_empty()));
+            }
+            "--ignore-key" => {
+                let v = args.next().ok_or_else(|| "Expected value after --ignore-key".to_string())?;
+                ignore_keys.insert(v);
+            }
+            "--only-keys" => {
+                let v = args.next().ok_or_else(|| "Expected value after --only-keys".to_string())?;
+                let set = only_keys.get_or_insert_with(std::collections::HashSet::new);
+                set.extend(v.split(',').map(|s| s.trim().to_string()).filter(|s| !s.is_empty()));
+            }
+            "--rel-tolerance-time" => {
+                let v = args
+                    .next()
+                    .ok_or_else(|| "Expected value after --rel-tolerance-time".to_string())?;
+                rel_tol_time =
+                    Some(v.parse::<f64>().map_err(|_| "Invalid float for --rel-tolerance-time".to_string())?);
+            }
+            "--rel-tolerance-val" => {
+                let v = args
+                    .next()
+                    .ok_or_else(|| "Expected value after --rel-tolerance-val".to_string())?;
+                rel_tol_val =
+                    Some(v.parse::<f64>().map_err(|_| "Invalid float for --rel-tolerance-val".to_string())?);
+            }
+            "--tolerance-key" => {
+                let v = args.next().ok_or_else(|| "Expected value after --tolerance-key".to_string())?;
+                let (key, val) = v
+                    .split_once('=')
+                    .ok_or_else(|| format!("Invalid --tolerance-key '{v}' (expected KEY=F64)"))?;
+                let val = val
+                    .parse::<f64>()
+                    .map_err(|_| format!("Invalid float '{val}' for --tolerance-key '{key}'"))?;
+                tolerance_keys.insert(key.to_string(), val);
+            }
+            "--value-offset-key" => {
+                let v = args.next().ok_or_else(|| "Expected value after --value-offset-key".to_string())?;
+                let (key, val) = v
+                    .split_once('=')
+                    .ok_or_else(|| format!("Invalid --value-offset-key '{v}' (expected KEY=OFFSET)"))?;
+                let val = val
+                    .parse::<f64>()
+                    .map_err(|_| format!("Invalid float '{val}' for --value-offset-key '{key}'"))?;
+                value_offset_keys.insert(key.to_string(), val);
+            }
+            "--auto-value-offset" => {
+                let v = args.next().ok_or_else(|| "Expected value after --auto-value-offset".to_string())?;
+                auto_value_offset_keys.insert(v);
+            }
+            "--max-errors" => {
+                let v = args.next().ok_or_else(|| "Expected value after --max-errors".to_string())?;
+                max_errors = v.parse::<usize>().map_err(|_| "Invalid integer for --max-errors".to_string())?;
+            }
+            "--format" => {
+                let v = args.next().ok_or_else(|| "Expected value after --format".to_string())?;
+                if v != "text" && v != "json" {
+                    return Err(format!("Invalid --format '{v}' (expected text or json)"));
+                }
+                format = v;
+            }
             other => {
                 return Err(format!("Unknown option for compare: {other}"));
             }
@@ -87,12 +395,134 @@ fn parse_compare_args(mut args: impl Iterator<Item = String>) -> Result<CompareO
     let tol_time = tol_time.ok_or_else(|| "Missing required --tolerance-time".to_string())?;
     let tol_val = tol_val.ok_or_else(|| "Missing required --tolerance-val".to_string())?;
 
-    Ok(CompareOptions {
-        gold,
-        test,
-        tol_time,
-        tol_val,
-    })
+    Ok((
+        CompareOptions {
+            gold,
+            test,
+            tol_time,
+            tol_val,
+            skip_before,
+            time_key,
+            unordered,
+            match_tiebreak,
+            require_monotonic,
+            per_type,
+            ignore_keys,
+            only_keys,
+            rel_tol_time,
+            rel_tol_val,
+            max_errors,
+            match_by_key,
+            tolerance_keys,
+            value_offset_keys,
+            auto_value_offset_keys,
+        },
+        format,
+    ))
+}
+
+fn parse_compare_meta_args(mut args: impl Iterator<Item = String>) -> Result<(PathBuf, PathBuf), String> {
+    let mut gold: Option<PathBuf> = None;
+    let mut test: Option<PathBuf> = None;
+
+    while let Some(arg) = args.next() {
+        match arg.as_str() {
+            "--help" | "-h" => {
+                print_compare_meta_help();
+                return Err(String::from("__HELP__"));
+            }
+            "--gold" => {
+                gold = Some(PathBuf::from(
+                    args.next().ok_or_else(|| "Expected value after --gold".to_string())?,
+                ));
+            }
+            "--test" => {
+                test = Some(PathBuf::from(
+                    args.next().ok_or_else(|| "Expected value after --test".to_string())?,
+                ));
+            }
+            other => {
+                return Err(format!("Unknown option for compare-meta: {other}"));
+            }
+        }
+    }
+
+    let gold = gold.ok_or_else(|| "Missing required --gold".to_string())?;
+    let test = test.ok_or_else(|| "Missing required --test".to_string())?;
+    Ok((gold, test))
+}
+
+fn parse_stats_args(mut args: impl Iterator<Item = String>) -> Result<(PathBuf, String, String), String> {
+    let mut input: Option<PathBuf> = None;
+    let mut time_key: String = "t_s".to_string();
+    let mut format: String = "text".to_string();
+
+    while let Some(arg) = args.next() {
+        match arg.as_str() {
+            "--help" | "-h" => {
+                print_stats_help();
+                return Err(String::from("__HELP__"));
+            }
+            "--input" => {
+                input = Some(PathBuf::from(
+                    args.next().ok_or_else(|| "Expected value after --input".to_string())?,
+                ));
+            }
+            "--time-key" => {
+                time_key = args.next().ok_or_else(|| "Expected value after --time-key".to_string())?;
+            }
+            "--format" => {
+                let v = args.next().ok_or_else(|| "Expected value after --format".to_string())?;
+                if v != "text" && v != "json" {
+                    return Err(format!("Invalid --format '{v}' (expected text or json)"));
+                }
+                format = v;
+            }
+            other => {
+                return Err(format!("Unknown option for stats: {other}"));
+            }
+        }
+    }
+
+    let input = input.ok_or_else(|| "Missing required --input".to_string())?;
+    Ok((input, time_key, format))
+}
+
+fn parse_validate_args(mut args: impl Iterator<Item = String>) -> Result<(PathBuf, usize, String), String> {
+    let mut input: Option<PathBuf> = None;
+    let mut max_report: usize = 20;
+    let mut format: String = "text".to_string();
+
+    while let Some(arg) = args.next() {
+        match arg.as_str() {
+            "--help" | "-h" => {
+                print_validate_help();
+                return Err(String::from("__HELP__"));
+            }
+            "--input" => {
+                input = Some(PathBuf::from(
+                    args.next().ok_or_else(|| "Expected value after --input".to_string())?,
+                ));
+            }
+            "--max-report" => {
+                let v = args.next().ok_or_else(|| "Expected value after --max-report".to_string())?;
+                max_report = v.parse::<usize>().map_err(|_| "Invalid integer for --max-report".to_string())?;
+            }
+            "--format" => {
+                let v = args.next().ok_or_else(|| "Expected value after --format".to_string())?;
+                if v != "text" && v != "json" {
+                    return Err(format!("Invalid --format '{v}' (expected text or json)"));
+                }
+                format = v;
+            }
+            other => {
+                return Err(format!("Unknown option for validate: {other}"));
+            }
+        }
+    }
+
+    let input = input.ok_or_else(|| "Missing required --input".to_string())?;
+    Ok((input, max_report, format))
 }
 
 fn main() -> ExitCode {
@@ -107,16 +537,106 @@ fn main() -> ExitCode {
     match sub.as_str() {
         "compare" => {
             match parse_compare_args(args) {
-                Ok(opts) => match run(opts) {
+                Ok((opts, format)) => match run(opts) {
                     Ok(summary) => {
+                        if format == "json" {
+                            println!(
+                                "{}",
+                                serde_json::json!({
+                                    "status": "ok",
+                                    "matched": summary.events,
+                                    "meta_gold": summary.meta_gold,
+                                    "meta_test": summary.meta_test,
+                                    "tol_time": summary.tol_time,
+                                    "tol_val": summary.tol_val,
+                                    "mismatches": Vec::<()>::new(),
+                                    "gold_parse_skips": summary.gold_parse_skips,
+                                    "test_parse_skips": summary.test_parse_skips,
+                                    "value_offsets_used": summary.value_offsets_used,
+                                })
+                            );
+                            return ExitCode::SUCCESS;
+                        }
                         println!(
-                            "OK: matched {} events (meta gold={}, test={}) within tol_time={}s tol_val={}",
-                            summary.events, summary.meta_gold, summary.meta_test, summary.tol_time, summary.tol_val
+                            "OK: matched {} events (meta gold={}, test={}) within tol_time={}s tol_val={} (skipped gold={}, test={})",
+                            summary.events, summary.meta_gold, summary.meta_test, summary.tol_time, summary.tol_val,
+                            summary.skipped_gold, summary.skipped_test
                         );
+                        println!("  max_time_delta={}s", summary.max_time_delta);
+                        match (summary.rel_tol_time, summary.rel_tol_val) {
+                            (None, None) => {}
+                            (rt, rv) => {
+                                println!(
+                                    "  relative tolerance active: time={} val={}",
+                                    rt.map(|v| v.to_string()).unwrap_or_else(|| "off".to_string()),
+                                    rv.map(|v| v.to_string()).unwrap_or_else(|| "off".to_string())
+                                );
+                            }
+                        }
+                        if summary.max_value_deltas.is_empty() {
+                            println!("  max_value_deltas: (no numeric payload fields observed)");
+                        } else {
+                            let mut keys: Vec<_> = summary.max_value_deltas.keys().collect();
+                            keys.sort();
+                            for key in keys {
+                                println!("  max_value_delta[{key}]={}", summary.max_value_deltas[key]);
+                            }
+                        }
+                        if !summary.type_counts.is_empty() {
+                            println!("  Per-type breakdown:");
+                            let mut types: Vec<_> = summary.type_counts.keys().collect();
+                            types.sort();
+                            for t in types {
+                                let (matched, mismatched) = summary.type_counts[t];
+                                println!("    {t}: matched={matched} mismatched={mismatched}");
+                            }
+                        }
+                        if !summary.gold_parse_skips.is_empty() {
+                            println!("  {}", compare::format_parse_skips("gold", &summary.gold_parse_skips));
+                        }
+                        if !summary.test_parse_skips.is_empty() {
+                            println!("  {}", compare::format_parse_skips("test", &summary.test_parse_skips));
+                        }
+                        if !summary.value_offsets_used.is_empty() {
+                            let mut keys: Vec<_> = summary.value_offsets_used.keys().collect();
+                            keys.sort();
+                            for key in keys {
+                                println!("  value_offset[{key}]={}", summary.value_offsets_used[key]);
+                            }
+                        }
                         ExitCode::SUCCESS
                     }
-                    Err(msg) => {
-                        eprintln!("COMPARE MISMATCH: {msg}");
+                    Err(compare::CompareError::Mismatch {
+                        events,
+                        meta_gold,
+                        meta_test,
+                        tol_time,
+                        tol_val,
+                        mismatches,
+                        gold_parse_skips,
+                        test_parse_skips,
+                        value_offsets_used,
+                        ..
+                    }) if format == "json" => {
+                        println!(
+                            "{}",
+                            serde_json::json!({
+                                "status": "mismatch",
+                                "matched": events,
+                                "meta_gold": meta_gold,
+                                "meta_test": meta_test,
+                                "tol_time": tol_time,
+                                "tol_val": tol_val,
+                                "mismatches": mismatches,
+                                "gold_parse_skips": gold_parse_skips,
+                                "test_parse_skips": test_parse_skips,
+                                "value_offsets_used": value_offsets_used,
+                            })
+                        );
+                        ExitCode::from(1)
+                    }
+                    Err(e) => {
+                        eprintln!("COMPARE MISMATCH: {e}");
                         ExitCode::from(1)
                     }
                 },
@@ -129,6 +649,137 @@ fn main() -> ExitCode {
                 }
             }
         }
+        "compare-meta" => match parse_compare_meta_args(args) {
+            Ok((gold, test)) => match run_meta(gold, test) {
+                Ok(diff) => {
+                    let has_diff = !diff.gold_only.is_empty() || !diff.test_only.is_empty() || !diff.differing.is_empty();
+                    println!(
+                        "Meta lines: gold={} test={}",
+                        diff.gold_meta_lines, diff.test_meta_lines
+                    );
+                    for k in &diff.gold_only {
+                        println!("  only in gold: {k}");
+                    }
+                    for k in &diff.test_only {
+                        println!("  only in test: {k}");
+                    }
+                    for (k, gv, tv) in &diff.differing {
+                        println!("  differs: {k} gold={gv} test={tv}");
+                    }
+                    if has_diff {
+                        eprintln!("META MISMATCH: config drift detected");
+                        ExitCode::from(1)
+                    } else {
+                        println!("OK: meta fields match");
+                        ExitCode::SUCCESS
+                    }
+                }
+                Err(e) => {
+                    eprintln!("Error: {e}");
+                    ExitCode::from(2)
+                }
+            },
+            Err(e) => {
+                if e != "__HELP__" {
+                    eprintln!("Error: {e}\n");
+                    print_compare_meta_help();
+                }
+                ExitCode::from(2)
+            }
+        },
+        "stats" => match parse_stats_args(args) {
+            Ok((input, time_key, format)) => match run_stats(&input, &time_key) {
+                Ok(stats) => {
+                    if format == "json" {
+                        println!("{}", serde_json::to_string(&stats).expect("TraceStats serializes"));
+                        return ExitCode::SUCCESS;
+                    }
+                    println!("events={} metas={}", stats.events, stats.metas);
+                    if !stats.type_counts.is_empty() {
+                        println!("Type breakdown:");
+                        let mut types: Vec<_> = stats.type_counts.keys().collect();
+                        types.sort();
+                        for t in types {
+                            println!("  {t}: {}", stats.type_counts[t]);
+                        }
+                    }
+                    match (stats.t_min, stats.t_max, stats.t_span) {
+                        (Some(lo), Some(hi), Some(span)) => {
+                            println!("{time_key}: min={lo} max={hi} span={span}");
+                        }
+                        _ => println!("{time_key}: (no events with a valid {time_key})"),
+                    }
+                    if stats.numeric_keys.is_empty() {
+                        println!("numeric payload keys: (none observed)");
+                    } else {
+                        println!("Numeric payload keys:");
+                        let mut keys: Vec<_> = stats.numeric_keys.keys().collect();
+                        keys.sort();
+                        for k in keys {
+                            let s = &stats.numeric_keys[k];
+                            println!("  {k}: min={} max={} mean={} count={}", s.min, s.max, s.mean, s.count);
+                        }
+                    }
+                    ExitCode::SUCCESS
+                }
+                Err(e) => {
+                    eprintln!("Error: {e}");
+                    ExitCode::from(2)
+                }
+            },
+            Err(e) => {
+                if e != "__HELP__" {
+                    eprintln!("Error: {e}\n");
+                    print_stats_help();
+                }
+                ExitCode::from(2)
+            }
+        },
+        "validate" => match parse_validate_args(args) {
+            Ok((input, max_report, format)) => match validate_file(&input) {
+                Ok(report) => {
+                    if format == "json" {
+                        println!(
+                            "{}",
+                            serde_json::json!({
+                                "lines": report.lines,
+                                "violation_count": report.violations.len(),
+                                "violations": report.violations,
+                            })
+                        );
+                        return if report.violations.is_empty() { ExitCode::SUCCESS } else { ExitCode::from(1) };
+                    }
+                    if report.violations.is_empty() {
+                        println!("OK: {} lines, no violations", report.lines);
+                        ExitCode::SUCCESS
+                    } else {
+                        println!(
+                            "INVALID: {} violation(s) in {} lines",
+                            report.violations.len(),
+                            report.lines
+                        );
+                        for v in report.violations.iter().take(max_report) {
+                            println!("  line {}: {} ({})", v.line_no, v.kind, v.detail);
+                        }
+                        if report.violations.len() > max_report {
+                            println!("  ... and {} more", report.violations.len() - max_report);
+                        }
+                        ExitCode::from(1)
+                    }
+                }
+                Err(e) => {
+                    eprintln!("Error: Failed to read file: {e}");
+                    ExitCode::from(2)
+                }
+            },
+            Err(e) => {
+                if e != "__HELP__" {
+                    eprintln!("Error: {e}\n");
+                    print_validate_help();
+                }
+                ExitCode::from(2)
+            }
+        },
         "--help" | "-h" => {
             print_top_help();
             ExitCode::SUCCESS
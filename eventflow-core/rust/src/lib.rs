@@ -1,6 +1,8 @@
 use numpy::{PyArray1, PyReadonlyArray1};
+use pyo3::types::{PyDict, PyList, PyTuple};
 use pyo3::{prelude::*, Py};
 use pyo3::create_exception;
+use pyo3::exceptions::PyValueError;
 use once_cell::sync::OnceCell;
 use std::sync::RwLock;
 use std::collections::VecDeque;
@@ -12,6 +14,21 @@ create_exception!(eventflow_core_native, FuseError, pyo3::exceptions::PyExceptio
 // Global optional logging sink (callable)
 static LOG_SINK: OnceCell<RwLock<Option<Py<PyAny>>>> = OnceCell::new();
 
+// Minimum level a message must meet to reach the sink. Default is `trace`
+// (rank 0), i.e. nothing is filtered unless `set_log_level` is called.
+static LOG_LEVEL: OnceCell<RwLock<u8>> = OnceCell::new();
+
+fn log_level_rank(level: &str) -> Option<u8> {
+    match level {
+        "trace" => Some(0),
+        "debug" => Some(1),
+        "info" => Some(2),
+        "warn" => Some(3),
+        "error" => Some(4),
+        _ => None,
+    }
+}
+
 #[pyfunction]
 fn is_ready() -> bool {
     true
@@ -22,35 +39,360 @@ fn sum_f32(a: f32, b: f32) -> f32 {
     a + b
 }
 
+/// Pairwise SIMD summation with Kahan compensation, used when the `simd`
+/// feature is enabled. Falls back to the caller when the slice can't be
+/// formed (e.g. non-contiguous input).
+#[cfg(feature = "simd")]
+fn simd_sum_f32(values: &[f32]) -> f32 {
+    use wide::f32x8;
+
+    let mut sum = f32x8::ZERO;
+    let mut comp = f32x8::ZERO;
+    let chunks = values.chunks_exact(8);
+    let remainder = chunks.remainder();
+    for chunk in chunks {
+        let v = f32x8::from(<[f32; 8]>::try_from(chunk).unwrap());
+        let y = v - comp;
+        let t = sum + y;
+        comp = (t - sum) - y;
+        sum = t;
+    }
+
+    let lanes = sum.to_array();
+    let mut total = 0.0f32;
+    let mut c = 0.0f32;
+    for &lane in lanes.iter() {
+        let y = lane - c;
+        let t = total + y;
+        c = (t - total) - y;
+        total = t;
+    }
+    for &x in remainder {
+        let y = x - c;
+        let t = total + y;
+        c = (t - total) - y;
+        total = t;
+    }
+    total
+}
+
+/// Plain (non-SIMD) element sum shared by all `sum_ndarray_*` variants, so
+/// adding a dtype never means re-deriving the reduction logic.
+fn sum_plain<T: Copy + std::iter::Sum>(a: &numpy::ndarray::ArrayView1<T>) -> T {
+    a.iter().copied().sum()
+}
+
 #[pyfunction]
 fn sum_ndarray_f32(_py: Python<'_>, arr: PyReadonlyArray1<f32>) -> PyResult<f32> {
     let a = arr.as_array();
-    let sum = a.sum();
-    Ok(sum)
+    #[cfg(feature = "simd")]
+    {
+        if let Some(slice) = a.as_slice() {
+            return Ok(simd_sum_f32(slice));
+        }
+    }
+    Ok(sum_plain(&a))
+}
+
+/// `f64` counterpart to `sum_ndarray_f32`, for downstream arrays that are
+/// already double-precision and shouldn't have to round-trip through `f32`
+/// just to call a sum reducer.
+#[pyfunction]
+fn sum_ndarray_f64(_py: Python<'_>, arr: PyReadonlyArray1<f64>) -> PyResult<f64> {
+    Ok(sum_plain(&arr.as_array()))
+}
+
+/// `i64` counterpart to `sum_ndarray_f32`, for integer-typed event counts and
+/// similar arrays that previously hit a confusing numpy dtype error.
+#[pyfunction]
+fn sum_ndarray_i64(_py: Python<'_>, arr: PyReadonlyArray1<i64>) -> PyResult<i64> {
+    Ok(sum_plain(&arr.as_array()))
+}
+
+/// Report build-time characteristics of the native extension, e.g. whether
+/// the `simd` feature was compiled in for `sum_ndarray_f32`.
+#[pyfunction]
+fn build_info(py: Python<'_>) -> PyResult<Py<PyDict>> {
+    let d = PyDict::new(py);
+    d.set_item("rust_enabled", true)?;
+    d.set_item("simd_enabled", cfg!(feature = "simd"))?;
+    Ok(d.unbind())
+}
+
+/// Quantize timestamps to a coarser grid of `resolution_ns`, preserving the
+/// event count (unlike bucketing, which aggregates). `mode` selects rounding
+/// direction: "round" (nearest, ties away from zero), "floor", or "ceil".
+/// Useful as a pre-pass for exact-match dedup downstream.
+#[pyfunction]
+fn quantize_time_i64<'py>(
+    py: Python<'py>,
+    t_ns: PyReadonlyArray1<i64>,
+    resolution_ns: i64,
+    mode: &str,
+) -> PyResult<Py<PyArray1<i64>>> {
+    if resolution_ns <= 0 {
+        return Err(FuseError::new_err("resolution_ns must be > 0"));
+    }
+    if mode != "round" && mode != "floor" && mode != "ceil" {
+        return Err(FuseError::new_err("mode must be 'round', 'floor', or 'ceil'"));
+    }
+    let a = t_ns.as_array();
+    let mut out = Vec::with_capacity(a.len());
+    for &t in a.iter() {
+        let q = match mode {
+            "floor" => t.div_euclid(resolution_ns),
+            "ceil" => -(-t).div_euclid(resolution_ns),
+            _ => {
+                let half = resolution_ns / 2;
+                if t >= 0 { (t + half) / resolution_ns } else { (t - half) / resolution_ns }
+            }
+        };
+        out.push(q * resolution_ns);
+    }
+    Ok(PyArray1::from_vec(py, out).unbind())
+}
+
+/// Inter-spike intervals: consecutive differences of a sorted spike-time
+/// array, for ISI histogram preprocessing. Returns length `n - 1` (empty for
+/// `n < 2`). Unsorted input would silently produce negative intervals, which
+/// has burned users who forgot to sort upstream, so this rejects it with
+/// `BucketError` instead.
+#[pyfunction]
+fn isi_i64<'py>(py: Python<'py>, times: PyReadonlyArray1<i64>) -> PyResult<Py<PyArray1<i64>>> {
+    let a = times.as_array();
+    if a.len() < 2 {
+        return Ok(PyArray1::from_vec(py, Vec::new()).unbind());
+    }
+    let mut out = Vec::with_capacity(a.len() - 1);
+    for w in a.windows(2) {
+        let (prev, next) = (w[0], w[1]);
+        if next < prev {
+            return Err(BucketError::new_err("times must be sorted ascending"));
+        }
+        out.push(next - prev);
+    }
+    Ok(PyArray1::from_vec(py, out).unbind())
+}
+
+/// Dense per-bin event-count histogram (raster/PSTH) over `[t_start_ns,
+/// t_end_ns)`, with `dt_ns`-wide bins. Unlike `bucket_sum_i64_f32`, which
+/// only emits buckets that actually saw an event, this returns every bin in
+/// range including zeros, since raster/PSTH plots need a dense axis. Events
+/// outside `[t_start_ns, t_end_ns)` are dropped, not clamped into the first
+/// or last bin.
+/// Returns: counts\[ceil((t_end_ns - t_start_ns) / dt_ns)\] as i64.
+#[pyfunction]
+fn histogram_counts_i64<'py>(
+    py: Python<'py>,
+    times: PyReadonlyArray1<i64>,
+    t_start_ns: i64,
+    t_end_ns: i64,
+    dt_ns: i64,
+) -> PyResult<Py<PyArray1<i64>>> {
+    if dt_ns <= 0 {
+        return Err(BucketError::new_err("dt_ns must be > 0"));
+    }
+    if t_end_ns <= t_start_ns {
+        return Err(BucketError::new_err("t_end_ns must be > t_start_ns"));
+    }
+    let span = t_end_ns - t_start_ns;
+    let n_bins = ((span + dt_ns - 1) / dt_ns) as usize;
+    let mut counts = vec![0i64; n_bins];
+
+    let a = times.as_array();
+    for &t in a.iter() {
+        if t < t_start_ns || t >= t_end_ns {
+            continue;
+        }
+        let idx = ((t - t_start_ns) / dt_ns) as usize;
+        counts[idx] += 1;
+    }
+
+    Ok(PyArray1::from_vec(py, counts).unbind())
 }
 
 /// Bucketize and sum values over fixed windows:
 /// - Inputs: times (ns, i64), values (f32), and dt_ns (i64)
 /// - For each event (t, v), assign bucket key k = floor(t / dt_ns) * dt_ns
-/// - Aggregate contiguous runs (groupby semantics) and emit at (k + dt_ns)
+/// - By default (`coalesce=false`), aggregates contiguous runs (groupby
+///   semantics) and emits at (k + dt_ns); if timestamps are interleaved
+///   across buckets, the same bucket key can be emitted more than once,
+///   since a run only breaks when the key actually changes.
+/// - With `coalesce=true`, every event is accumulated into a `BTreeMap`
+///   keyed by bucket instead, so each distinct bucket is emitted exactly
+///   once regardless of interleaving, in ascending key order.
 /// Returns: (times_out_ns: i64[], sums_out: f32[])
 #[pyfunction]
+#[pyo3(signature = (t_ns, vals, dt_ns, coalesce=false))]
 fn bucket_sum_i64_f32<'py>(
     py: Python<'py>,
     t_ns: PyReadonlyArray1<i64>,
     vals: PyReadonlyArray1<f32>,
     dt_ns: i64,
+    coalesce: bool,
+) -> PyResult<(Py<PyArray1<i64>>, Py<PyArray1<f32>>)> {
+    if dt_ns <= 0 {
+        return Err(BucketError::new_err("dt_ns must be > 0"));
+    }
+    let t_a = t_ns.as_array();
+    let v_a = vals.as_array();
+    if t_a.len() != v_a.len() {
+        return Err(BucketError::new_err("t_ns and vals must have the same length"));
+    }
+
+    if t_a.len() == 0 {
+        let t_arr = PyArray1::from_vec(py, Vec::<i64>::new()).unbind();
+        let v_arr = PyArray1::from_vec(py, Vec::<f32>::new()).unbind();
+        return Ok((t_arr, v_arr));
+    }
+
+    if coalesce {
+        let mut acc: std::collections::BTreeMap<i64, f64> = std::collections::BTreeMap::new();
+        for (&t, &v) in t_a.iter().zip(v_a.iter()) {
+            let key = (t / dt_ns) * dt_ns;
+            *acc.entry(key).or_insert(0.0) += v as f64;
+        }
+        let mut out_t: Vec<i64> = Vec::with_capacity(acc.len());
+        let mut out_v: Vec<f32> = Vec::with_capacity(acc.len());
+        for (key, sum) in acc {
+            out_t.push(key + dt_ns);
+            out_v.push(sum as f32);
+        }
+        let t_arr = PyArray1::from_vec(py, out_t).unbind();
+        let v_arr = PyArray1::from_vec(py, out_v).unbind();
+        return Ok((t_arr, v_arr));
+    }
+
+    // Contiguous-run aggregation to mirror itertools.groupby behavior
+    let mut out_t: Vec<i64> = Vec::new();
+    let mut out_v: Vec<f32> = Vec::new();
+
+    let mut prev_key: i64 = (t_a[0] / dt_ns) * dt_ns;
+    let mut acc_f64: f64 = v_a[0] as f64;
+
+    for (&t, &v) in t_a.iter().zip(v_a.iter()).skip(1) {
+        let key = (t / dt_ns) * dt_ns;
+        if key == prev_key {
+            acc_f64 += v as f64;
+        } else {
+            // Flush previous bucket at boundary (k + dt)
+            out_t.push(prev_key + dt_ns);
+            out_v.push(acc_f64 as f32);
+            // Start new bucket
+            prev_key = key;
+            acc_f64 = v as f64;
+        }
+    }
+
+    // Flush final bucket
+    out_t.push(prev_key + dt_ns);
+    out_v.push(acc_f64 as f32);
+
+    let t_arr = PyArray1::from_vec(py, out_t).unbind();
+    let v_arr = PyArray1::from_vec(py, out_v).unbind();
+    Ok((t_arr, v_arr))
+}
+
+/// Neumaier (improved Kahan) compensated summation step: folds `x` into
+/// `sum`, tracking the low-order bits lost to rounding in `c` so they can be
+/// added back in at the end, instead of dropping them the way plain `f64`
+/// accumulation would once a bucket aggregates millions of small values.
+fn kahan_add(sum: f64, c: &mut f64, x: f64) -> f64 {
+    let t = sum + x;
+    if sum.abs() >= x.abs() {
+        *c += (sum - t) + x;
+    } else {
+        *c += (x - t) + sum;
+    }
+    t
+}
+
+/// Same contiguous-run bucketing as `bucket_sum_i64_f32`, but the accumulator
+/// uses Neumaier-compensated summation and the sums are returned as `f64`
+/// instead of being cast down to `f32` on flush. For energy-integral style
+/// traces where a bucket aggregates millions of small values and the total
+/// must match a reference to 1e-9 relative, the plain `f64`-then-cast-to-f32
+/// path in `bucket_sum_i64_f32` loses too much precision; this is the
+/// higher-precision sibling for those callers. The time array is identical
+/// to `bucket_sum_i64_f32`.
+/// Returns: (times_out_ns: i64[], sums_out: f64[])
+#[pyfunction]
+fn bucket_sum_i64_f64<'py>(
+    py: Python<'py>,
+    t_ns: PyReadonlyArray1<i64>,
+    vals: PyReadonlyArray1<f32>,
+    dt_ns: i64,
+) -> PyResult<(Py<PyArray1<i64>>, Py<PyArray1<f64>>)> {
+    if dt_ns <= 0 {
+        return Err(BucketError::new_err("dt_ns must be > 0"));
+    }
+    let t_a = t_ns.as_array();
+    let v_a = vals.as_array();
+    if t_a.len() != v_a.len() {
+        return Err(BucketError::new_err("t_ns and vals must have the same length"));
+    }
+
+    let mut out_t: Vec<i64> = Vec::new();
+    let mut out_v: Vec<f64> = Vec::new();
+
+    if t_a.len() == 0 {
+        let t_arr = PyArray1::from_vec(py, out_t).unbind();
+        let v_arr = PyArray1::from_vec(py, out_v).unbind();
+        return Ok((t_arr, v_arr));
+    }
+
+    let mut prev_key: i64 = (t_a[0] / dt_ns) * dt_ns;
+    let mut acc: f64 = v_a[0] as f64;
+    let mut comp: f64 = 0.0;
+
+    for (&t, &v) in t_a.iter().zip(v_a.iter()).skip(1) {
+        let key = (t / dt_ns) * dt_ns;
+        if key == prev_key {
+            acc = kahan_add(acc, &mut comp, v as f64);
+        } else {
+            out_t.push(prev_key + dt_ns);
+            out_v.push(acc + comp);
+            prev_key = key;
+            acc = v as f64;
+            comp = 0.0;
+        }
+    }
+
+    out_t.push(prev_key + dt_ns);
+    out_v.push(acc + comp);
+
+    let t_arr = PyArray1::from_vec(py, out_t).unbind();
+    let v_arr = PyArray1::from_vec(py, out_v).unbind();
+    Ok((t_arr, v_arr))
+}
+
+/// Bucketize values over fixed windows with a choice of reduction, same
+/// contiguous-run grouping semantics as `bucket_sum_i64_f32` (a run only
+/// breaks when the bucket key changes, so interleaved buckets can be emitted
+/// more than once). `op` is one of `"sum"`, `"mean"`, `"min"`, `"max"`, or
+/// `"count"`; unknown values raise `BucketError`. `count` emits an
+/// integer-valued f32 (the number of events folded into that run).
+/// Returns: (times_out_ns: i64[], reduced_out: f32[])
+#[pyfunction]
+fn bucket_reduce_i64_f32<'py>(
+    py: Python<'py>,
+    t_ns: PyReadonlyArray1<i64>,
+    vals: PyReadonlyArray1<f32>,
+    dt_ns: i64,
+    op: &str,
 ) -> PyResult<(Py<PyArray1<i64>>, Py<PyArray1<f32>>)> {
     if dt_ns <= 0 {
         return Err(BucketError::new_err("dt_ns must be > 0"));
     }
+    if !matches!(op, "sum" | "mean" | "min" | "max" | "count") {
+        return Err(BucketError::new_err("op must be one of 'sum', 'mean', 'min', 'max', 'count'"));
+    }
     let t_a = t_ns.as_array();
     let v_a = vals.as_array();
     if t_a.len() != v_a.len() {
         return Err(BucketError::new_err("t_ns and vals must have the same length"));
     }
 
-    // Implement contiguous-run aggregation to mirror itertools.groupby behavior
     let mut out_t: Vec<i64> = Vec::new();
     let mut out_v: Vec<f32> = Vec::new();
 
@@ -60,66 +402,759 @@ fn bucket_sum_i64_f32<'py>(
         return Ok((t_arr, v_arr));
     }
 
+    let reduce = |sum: f64, min: f32, max: f32, count: usize| -> f32 {
+        match op {
+            "sum" => sum as f32,
+            "mean" => (sum / count as f64) as f32,
+            "min" => min,
+            "max" => max,
+            _ => count as f32,
+        }
+    };
+
+    let mut prev_key: i64 = (t_a[0] / dt_ns) * dt_ns;
+    let mut acc_f64: f64 = v_a[0] as f64;
+    let mut acc_min: f32 = v_a[0];
+    let mut acc_max: f32 = v_a[0];
+    let mut acc_count: usize = 1;
+
+    for (&t, &v) in t_a.iter().zip(v_a.iter()).skip(1) {
+        let key = (t / dt_ns) * dt_ns;
+        if key == prev_key {
+            acc_f64 += v as f64;
+            acc_min = acc_min.min(v);
+            acc_max = acc_max.max(v);
+            acc_count += 1;
+        } else {
+            out_t.push(prev_key + dt_ns);
+            out_v.push(reduce(acc_f64, acc_min, acc_max, acc_count));
+            prev_key = key;
+            acc_f64 = v as f64;
+            acc_min = v;
+            acc_max = v;
+            acc_count = 1;
+        }
+    }
+
+    out_t.push(prev_key + dt_ns);
+    out_v.push(reduce(acc_f64, acc_min, acc_max, acc_count));
+
+    let t_arr = PyArray1::from_vec(py, out_t).unbind();
+    let v_arr = PyArray1::from_vec(py, out_v).unbind();
+    Ok((t_arr, v_arr))
+}
+
+/// Bucketize and sum values over fixed windows, same grouping semantics as
+/// `bucket_sum_i64_f32`, but additionally tracks the min/max original event
+/// timestamp contributing to each contiguous run and returns them as
+/// `first_ts`/`last_ts` (one entry per emitted bucket, same order as the
+/// other outputs). Lets callers compute effective bucket occupancy duration
+/// and detect sparse buckets where events cluster at one edge, without
+/// changing the return shape of the original function for existing callers.
+/// Returns: (times_out_ns: i64[], sums_out: f32[], first_ts: i64[], last_ts: i64[])
+#[pyfunction]
+fn bucket_sum_span_i64_f32<'py>(
+    py: Python<'py>,
+    t_ns: PyReadonlyArray1<i64>,
+    vals: PyReadonlyArray1<f32>,
+    dt_ns: i64,
+) -> PyResult<(Py<PyArray1<i64>>, Py<PyArray1<f32>>, Py<PyArray1<i64>>, Py<PyArray1<i64>>)> {
+    if dt_ns <= 0 {
+        return Err(BucketError::new_err("dt_ns must be > 0"));
+    }
+    let t_a = t_ns.as_array();
+    let v_a = vals.as_array();
+    if t_a.len() != v_a.len() {
+        return Err(BucketError::new_err("t_ns and vals must have the same length"));
+    }
+
+    let mut out_t: Vec<i64> = Vec::new();
+    let mut out_v: Vec<f32> = Vec::new();
+    let mut out_first: Vec<i64> = Vec::new();
+    let mut out_last: Vec<i64> = Vec::new();
+
+    if t_a.len() == 0 {
+        let t_arr = PyArray1::from_vec(py, out_t).unbind();
+        let v_arr = PyArray1::from_vec(py, out_v).unbind();
+        let first_arr = PyArray1::from_vec(py, out_first).unbind();
+        let last_arr = PyArray1::from_vec(py, out_last).unbind();
+        return Ok((t_arr, v_arr, first_arr, last_arr));
+    }
+
     let mut prev_key: i64 = (t_a[0] / dt_ns) * dt_ns;
     let mut acc_f64: f64 = v_a[0] as f64;
+    let mut run_first: i64 = t_a[0];
+    let mut run_last: i64 = t_a[0];
 
     for (&t, &v) in t_a.iter().zip(v_a.iter()).skip(1) {
         let key = (t / dt_ns) * dt_ns;
         if key == prev_key {
             acc_f64 += v as f64;
+            run_first = run_first.min(t);
+            run_last = run_last.max(t);
         } else {
             // Flush previous bucket at boundary (k + dt)
             out_t.push(prev_key + dt_ns);
             out_v.push(acc_f64 as f32);
+            out_first.push(run_first);
+            out_last.push(run_last);
             // Start new bucket
             prev_key = key;
             acc_f64 = v as f64;
+            run_first = t;
+            run_last = t;
         }
     }
 
     // Flush final bucket
     out_t.push(prev_key + dt_ns);
     out_v.push(acc_f64 as f32);
+    out_first.push(run_first);
+    out_last.push(run_last);
+
+    let t_arr = PyArray1::from_vec(py, out_t).unbind();
+    let v_arr = PyArray1::from_vec(py, out_v).unbind();
+    let first_arr = PyArray1::from_vec(py, out_first).unbind();
+    let last_arr = PyArray1::from_vec(py, out_last).unbind();
+    Ok((t_arr, v_arr, first_arr, last_arr))
+}
+
+/// Bucketize and sum values over fixed windows, capped to the last `max_events`
+/// values seen within each contiguous bucket run (a ring buffer drops the
+/// oldest value once the cap is exceeded). Models a saturating integrator,
+/// distinct from `bucket_sum_i64_f32` which sums the whole run unconditionally.
+/// Returns: (times_out_ns: i64[], sums_out: f32[])
+#[pyfunction]
+fn bucket_sum_capped_i64_f32<'py>(
+    py: Python<'py>,
+    t_ns: PyReadonlyArray1<i64>,
+    vals: PyReadonlyArray1<f32>,
+    dt_ns: i64,
+    max_events: usize,
+) -> PyResult<(Py<PyArray1<i64>>, Py<PyArray1<f32>>)> {
+    if dt_ns <= 0 {
+        return Err(BucketError::new_err("dt_ns must be > 0"));
+    }
+    if max_events == 0 {
+        return Err(BucketError::new_err("max_events must be > 0"));
+    }
+    let t_a = t_ns.as_array();
+    let v_a = vals.as_array();
+    if t_a.len() != v_a.len() {
+        return Err(BucketError::new_err("t_ns and vals must have the same length"));
+    }
+
+    let mut out_t: Vec<i64> = Vec::new();
+    let mut out_v: Vec<f32> = Vec::new();
+
+    if t_a.len() == 0 {
+        let t_arr = PyArray1::from_vec(py, out_t).unbind();
+        let v_arr = PyArray1::from_vec(py, out_v).unbind();
+        return Ok((t_arr, v_arr));
+    }
+
+    let mut prev_key: i64 = (t_a[0] / dt_ns) * dt_ns;
+    let mut ring: VecDeque<f32> = VecDeque::with_capacity(max_events);
+    ring.push_back(v_a[0]);
+
+    for (&t, &v) in t_a.iter().zip(v_a.iter()).skip(1) {
+        let key = (t / dt_ns) * dt_ns;
+        if key == prev_key {
+            if ring.len() == max_events {
+                ring.pop_front();
+            }
+            ring.push_back(v);
+        } else {
+            let sum: f64 = ring.iter().map(|&x| x as f64).sum();
+            out_t.push(prev_key + dt_ns);
+            out_v.push(sum as f32);
+            prev_key = key;
+            ring.clear();
+            ring.push_back(v);
+        }
+    }
+
+    let sum: f64 = ring.iter().map(|&x| x as f64).sum();
+    out_t.push(prev_key + dt_ns);
+    out_v.push(sum as f32);
 
     let t_arr = PyArray1::from_vec(py, out_t).unbind();
     let v_arr = PyArray1::from_vec(py, out_v).unbind();
     Ok((t_arr, v_arr))
 }
 
+/// LIF-style leaky integration over a spike train: walks events in time
+/// order, decays the running state by `exp(-(t - t_prev) / tau_ns)` before
+/// adding each `val`, and returns the state sampled at each event time.
+/// Gives a cheap neuromorphic membrane trace without a Python loop over
+/// millions of events.
+/// Returns: (times_out_ns: i64[], states_out: f32[]), same length as input.
+#[pyfunction]
+fn leaky_integrate_i64_f32<'py>(
+    py: Python<'py>,
+    t_ns: PyReadonlyArray1<i64>,
+    vals: PyReadonlyArray1<f32>,
+    tau_ns: f64,
+) -> PyResult<(Py<PyArray1<i64>>, Py<PyArray1<f32>>)> {
+    if tau_ns <= 0.0 {
+        return Err(BucketError::new_err("tau_ns must be > 0"));
+    }
+    let t_a = t_ns.as_array();
+    let v_a = vals.as_array();
+    if t_a.len() != v_a.len() {
+        return Err(BucketError::new_err("t_ns and vals must have the same length"));
+    }
+
+    let mut out_t: Vec<i64> = Vec::with_capacity(t_a.len());
+    let mut out_v: Vec<f32> = Vec::with_capacity(t_a.len());
+
+    let mut state: f64 = 0.0;
+    let mut prev_t: Option<i64> = None;
+
+    for (&t, &v) in t_a.iter().zip(v_a.iter()) {
+        if let Some(pt) = prev_t {
+            let dt = (t - pt) as f64;
+            state *= (-dt / tau_ns).exp();
+        }
+        state += v as f64;
+        prev_t = Some(t);
+        out_t.push(t);
+        out_v.push(state as f32);
+    }
+
+    let t_arr = PyArray1::from_vec(py, out_t).unbind();
+    let v_arr = PyArray1::from_vec(py, out_v).unbind();
+    Ok((t_arr, v_arr))
+}
+
+/// Threshold-crossing spike generator: runs the same leaky integration as
+/// `leaky_integrate_i64_f32`, but instead of returning the membrane trace,
+/// emits a spike time whenever the membrane crosses `threshold`. Each
+/// crossing subtracts `reset` from the membrane (or resets it fully to `0`
+/// when `reset` equals `threshold`), so a single large input that pushes the
+/// membrane over threshold multiple times in one step emits multiple spikes
+/// at that same timestamp. Returns the spike times as `PyArray1<i64>`.
+#[pyfunction]
+fn integrate_and_fire_i64_f32<'py>(
+    py: Python<'py>,
+    t_ns: PyReadonlyArray1<i64>,
+    vals: PyReadonlyArray1<f32>,
+    tau_ns: f64,
+    threshold: f32,
+    reset: f32,
+) -> PyResult<Py<PyArray1<i64>>> {
+    if tau_ns <= 0.0 {
+        return Err(FuseError::new_err("tau_ns must be > 0"));
+    }
+    if reset <= 0.0 {
+        return Err(FuseError::new_err("reset must be > 0"));
+    }
+    let t_a = t_ns.as_array();
+    let v_a = vals.as_array();
+    if t_a.len() != v_a.len() {
+        return Err(FuseError::new_err("t_ns and vals must have the same length"));
+    }
+
+    let mut out_t: Vec<i64> = Vec::new();
+    let mut state: f64 = 0.0;
+    let mut prev_t: Option<i64> = None;
+    let threshold = threshold as f64;
+    let reset = reset as f64;
+    let full_reset = reset == threshold;
+
+    for (&t, &v) in t_a.iter().zip(v_a.iter()) {
+        if let Some(pt) = prev_t {
+            let dt = (t - pt) as f64;
+            state *= (-dt / tau_ns).exp();
+        }
+        state += v as f64;
+        prev_t = Some(t);
+
+        while state >= threshold {
+            out_t.push(t);
+            if full_reset {
+                state = 0.0;
+            } else {
+                state -= reset;
+            }
+        }
+    }
+
+    Ok(PyArray1::from_vec(py, out_t).unbind())
+}
+
+/// Overlapping trailing-window running sum, distinct from the non-overlapping
+/// fixed windows of `bucket_sum_i64_f32`: for each event, emits the sum of
+/// all values within `[t - window_ns, t]`. Maintains a `VecDeque` of `(t, v)`
+/// and a running accumulator, pruning the front when older than the cutoff —
+/// the exact pruning pattern already used by the fuse detector's merge loop.
+/// Returns: (times_out_ns: i64[], windowed_sums: f32[]), aligned 1:1 with input.
+#[pyfunction]
+fn sliding_sum_i64_f32<'py>(
+    py: Python<'py>,
+    t_ns: PyReadonlyArray1<i64>,
+    vals: PyReadonlyArray1<f32>,
+    window_ns: i64,
+) -> PyResult<(Py<PyArray1<i64>>, Py<PyArray1<f32>>)> {
+    if window_ns <= 0 {
+        return Err(BucketError::new_err("window_ns must be > 0"));
+    }
+    let t_a = t_ns.as_array();
+    let v_a = vals.as_array();
+    if t_a.len() != v_a.len() {
+        return Err(BucketError::new_err("t_ns and vals must have the same length"));
+    }
+
+    let mut buf: VecDeque<(i64, f32)> = VecDeque::new();
+    let mut acc: f64 = 0.0;
+    let mut out_t: Vec<i64> = Vec::with_capacity(t_a.len());
+    let mut out_v: Vec<f32> = Vec::with_capacity(t_a.len());
+
+    for (&t, &v) in t_a.iter().zip(v_a.iter()) {
+        buf.push_back((t, v));
+        acc += v as f64;
+        let cutoff = t - window_ns;
+        while let Some(&(front_t, front_v)) = buf.front() {
+            if front_t < cutoff {
+                acc -= front_v as f64;
+                buf.pop_front();
+            } else {
+                break;
+            }
+        }
+        out_t.push(t);
+        out_v.push(acc as f32);
+    }
+
+    let t_arr = PyArray1::from_vec(py, out_t).unbind();
+    let v_arr = PyArray1::from_vec(py, out_v).unbind();
+    Ok((t_arr, v_arr))
+}
+
+/// Pure-Rust core shared by `fuse_coincidence_i64`, `fuse_coincidence_batched`,
+/// and `fuse_coincidence_asym_i64`: sliding-window coincidence detection over
+/// two sorted-by-merge timestamp streams, optionally also collecting A
+/// timestamps that found no B partner, and optionally collecting timestamps
+/// where one buffer is heavily outnumbering the other (see `imbalance_ratio`
+/// on `fuse_coincidence_i64`). `buf_a` is pruned against `t - window_a_ns` and
+/// `buf_b` against `t - window_b_ns` independently, so a caller with a single
+/// shared window just passes the same value for both.
+#[allow(clippy::too_many_arguments)]
+fn fuse_pair_core(
+    a: &[i64],
+    b: &[i64],
+    window_a_ns: i64,
+    window_b_ns: i64,
+    min_count: usize,
+    min_density: f64,
+    emit_misses: bool,
+    relative_ts: bool,
+    imbalance_ratio: Option<f64>,
+    refractory_ns: i64,
+    require_both: bool,
+) -> (Vec<i64>, Vec<f32>, Vec<i64>, Vec<i64>) {
+    let mut merged: Vec<(i64, u8)> = Vec::with_capacity(a.len() + b.len());
+    for &t in a.iter() { merged.push((t, 0)); }
+    for &t in b.iter() { merged.push((t, 1)); }
+    merged.sort_by_key(|x| x.0);
+
+    let mut buf_a: VecDeque<i64> = VecDeque::new();
+    let mut buf_b: VecDeque<i64> = VecDeque::new();
+    let mut out_t: Vec<i64> = Vec::new();
+    let mut out_v: Vec<f32> = Vec::new();
+    let mut miss_t: Vec<i64> = Vec::new();
+    let mut imbalance_t: Vec<i64> = Vec::new();
+    let mut last_emit: Option<i64> = None;
+
+    for (t, src) in merged.into_iter() {
+        if src == 0 { buf_a.push_back(t); } else { buf_b.push_back(t); }
+        let cutoff_a = t - window_a_ns;
+        let cutoff_b = t - window_b_ns;
+
+        while let Some(&front) = buf_a.front() {
+            if front < cutoff_a { buf_a.pop_front(); } else { break; }
+        }
+        while let Some(&front) = buf_b.front() {
+            if front < cutoff_b { buf_b.pop_front(); } else { break; }
+        }
+
+        let total = buf_a.len() + buf_b.len();
+        let density = total as f64 / window_a_ns.max(window_b_ns) as f64;
+        let refractory_ok = last_emit.is_none_or(|le| t - le > refractory_ns);
+        let both_ok = !require_both || (!buf_a.is_empty() && !buf_b.is_empty());
+        if total >= min_count && density >= min_density && both_ok && refractory_ok {
+            if relative_ts {
+                let earliest = buf_a.front().copied().unwrap().min(buf_b.front().copied().unwrap());
+                out_t.push(t - earliest);
+            } else {
+                out_t.push(t);
+            }
+            out_v.push(1.0f32);
+            last_emit = Some(t);
+        }
+        if emit_misses && src == 0 && buf_b.is_empty() {
+            miss_t.push(t);
+        }
+        if let Some(ratio) = imbalance_ratio {
+            let hi = buf_a.len().max(buf_b.len()) as f64;
+            let lo = buf_a.len().min(buf_b.len()).max(1) as f64;
+            if hi / lo >= ratio {
+                imbalance_t.push(t);
+            }
+        }
+    }
+
+    (out_t, out_v, miss_t, imbalance_t)
+}
+
 /// Coincidence fuse detector:
 /// Given event times for streams A and B, a window in ns, and min_count,
 /// emit a coincidence at time t when both buffers have at least one event
 /// within [t - window, t], and the total count across A and B in the window
 /// is at least min_count. Returns times and values (all ones).
+///
+/// `min_density`: rate gate computed as `total / window_ns`, applied in addition
+/// to `min_count`. Suppresses coincidences during low-activity noise that would
+/// otherwise pass a raw count threshold, and is more robust than `min_count`
+/// alone when sweeping window sizes.
+///
+/// `emit_misses`: when true, also returns a third array of A timestamps at
+/// which `buf_b` was empty (i.e. that A event found no B partner within the
+/// window), so callers can quantify unmatched activity in the same pass
+/// instead of reconstructing it with a set difference.
+///
+/// `relative_ts`: when true, each emitted timestamp is `t - earliest_event_in_window`
+/// (the offset of the match from the earliest of the two buffer fronts at emission
+/// time) instead of the absolute `t`. Useful for feeding a jitter histogram, where
+/// only the within-window timing matters and the absolute clock does not.
+///
+/// `imbalance_ratio`: when set, independent of the AND-coincidence condition
+/// above, also emits a timestamp whenever `max(|buf_a|, |buf_b|) / max(min(|buf_a|,
+/// |buf_b|), 1) >= imbalance_ratio` within the window — i.e. one stream is
+/// heavily outnumbering (or entirely missing relative to) the other. Captures
+/// sustained single-stream activity (e.g. one sensor stuck on) that the
+/// symmetric coincidence logic specifically excludes, in the same pass as the
+/// coincidence signal. Appended as the last output array.
+///
+/// `refractory_ns`: when greater than 0, suppresses any emission occurring
+/// within `refractory_ns` of the previously emitted coincidence time,
+/// preventing a dense burst of coincidences from flooding downstream
+/// consumers during a sustained overlap. `0` (the default) preserves the
+/// original behavior of emitting every qualifying instant. Must be >= 0.
+///
+/// `require_both`: when true (the default), emission additionally requires
+/// both `buf_a` and `buf_b` to be non-empty, i.e. a genuine two-stream
+/// coincidence. When false, that requirement is dropped and a qualifying
+/// instant is emitted as soon as the combined count reaches `min_count`,
+/// even if every contributing event came from a single stream -- useful for
+/// detectors where sustained activity from either stream alone is already
+/// meaningful. This changes what counts as a "coincidence": with
+/// `require_both=false`, `min_count` alone becomes the effective gate, so
+/// callers relying on the AND-of-both-streams semantics must keep the
+/// default.
 #[pyfunction]
+#[allow(clippy::too_many_arguments)]
+#[pyo3(signature = (t_a, t_b, window_ns, min_count, min_density=0.0, emit_misses=false, relative_ts=false, imbalance_ratio=None, refractory_ns=0, require_both=true))]
 fn fuse_coincidence_i64<'py>(
     py: Python<'py>,
     t_a: PyReadonlyArray1<i64>,
     t_b: PyReadonlyArray1<i64>,
     window_ns: i64,
     min_count: usize,
+    min_density: f64,
+    emit_misses: bool,
+    relative_ts: bool,
+    imbalance_ratio: Option<f64>,
+    refractory_ns: i64,
+    require_both: bool,
+) -> PyResult<Py<PyAny>> {
+    if window_ns <= 0 {
+        return Err(FuseError::new_err("window_ns must be > 0"));
+    }
+    if refractory_ns < 0 {
+        return Err(FuseError::new_err("refractory_ns must be >= 0"));
+    }
+    let a = t_a.as_array().to_vec();
+    let b = t_b.as_array().to_vec();
+    let (out_t, out_v, miss_t, imbalance_t) = fuse_pair_core(
+        &a,
+        &b,
+        window_ns,
+        window_ns,
+        min_count,
+        min_density,
+        emit_misses,
+        relative_ts,
+        imbalance_ratio,
+        refractory_ns,
+        require_both,
+    );
+
+    let t_arr = PyArray1::from_vec(py, out_t);
+    let v_arr = PyArray1::from_vec(py, out_v);
+    let mut parts: Vec<Py<PyAny>> = vec![t_arr.into_any().unbind(), v_arr.into_any().unbind()];
+    if emit_misses {
+        parts.push(PyArray1::from_vec(py, miss_t).into_any().unbind());
+    }
+    if imbalance_ratio.is_some() {
+        parts.push(PyArray1::from_vec(py, imbalance_t).into_any().unbind());
+    }
+    Ok(PyTuple::new(py, parts)?.into_any().unbind())
+}
+
+/// Same emission condition as `fuse_coincidence_i64` (ignoring its
+/// `emit_misses`/`relative_ts`/`imbalance_ratio` extras), but instead of a
+/// flat value of `1.0` returns the in-window buffer sizes for A and B at the
+/// moment of emission, so a caller can tell an A-heavy coincidence from a
+/// B-heavy one without re-running the windowing logic itself.
+/// Returns: (times, count_a, count_b).
+#[pyfunction]
+#[pyo3(signature = (t_a, t_b, window_ns, min_count, min_density=0.0))]
+fn fuse_coincidence_i64_detailed<'py>(
+    py: Python<'py>,
+    t_a: PyReadonlyArray1<i64>,
+    t_b: PyReadonlyArray1<i64>,
+    window_ns: i64,
+    min_count: usize,
+    min_density: f64,
+) -> PyResult<(Py<PyArray1<i64>>, Py<PyArray1<i64>>, Py<PyArray1<i64>>)> {
+    if window_ns <= 0 {
+        return Err(FuseError::new_err("window_ns must be > 0"));
+    }
+    let a = t_a.as_array();
+    let b = t_b.as_array();
+
+    let mut merged: Vec<(i64, u8)> = Vec::with_capacity(a.len() + b.len());
+    for &t in a.iter() {
+        merged.push((t, 0));
+    }
+    for &t in b.iter() {
+        merged.push((t, 1));
+    }
+    merged.sort_by_key(|x| x.0);
+
+    let mut buf_a: VecDeque<i64> = VecDeque::new();
+    let mut buf_b: VecDeque<i64> = VecDeque::new();
+    let mut out_t: Vec<i64> = Vec::new();
+    let mut out_count_a: Vec<i64> = Vec::new();
+    let mut out_count_b: Vec<i64> = Vec::new();
+
+    for (t, src) in merged.into_iter() {
+        if src == 0 {
+            buf_a.push_back(t);
+        } else {
+            buf_b.push_back(t);
+        }
+        let cutoff = t - window_ns;
+
+        while let Some(&front) = buf_a.front() {
+            if front < cutoff { buf_a.pop_front(); } else { break; }
+        }
+        while let Some(&front) = buf_b.front() {
+            if front < cutoff { buf_b.pop_front(); } else { break; }
+        }
+
+        let total = buf_a.len() + buf_b.len();
+        let density = total as f64 / window_ns as f64;
+        if total >= min_count && density >= min_density && !buf_a.is_empty() && !buf_b.is_empty() {
+            out_t.push(t);
+            out_count_a.push(buf_a.len() as i64);
+            out_count_b.push(buf_b.len() as i64);
+        }
+    }
+
+    let t_arr = PyArray1::from_vec(py, out_t).unbind();
+    let a_arr = PyArray1::from_vec(py, out_count_a).unbind();
+    let b_arr = PyArray1::from_vec(py, out_count_b).unbind();
+    Ok((t_arr, a_arr, b_arr))
+}
+
+/// Coincidence fuse with independent per-stream windows: like
+/// `fuse_coincidence_i64`'s basic AND-coincidence condition (both buffers
+/// non-empty and the combined count at least `min_count`), but `buf_a` is
+/// pruned against `t - window_a_ns` and `buf_b` against `t - window_b_ns`
+/// independently instead of a single shared `window_ns`. Useful when the two
+/// streams have different latencies, so a symmetric window would either
+/// over-count the faster stream or miss genuine matches on the slower one.
+/// `window_a_ns` and `window_b_ns` must both be > 0.
+#[pyfunction]
+#[pyo3(signature = (t_a, t_b, window_a_ns, window_b_ns, min_count))]
+fn fuse_coincidence_asym_i64<'py>(
+    py: Python<'py>,
+    t_a: PyReadonlyArray1<i64>,
+    t_b: PyReadonlyArray1<i64>,
+    window_a_ns: i64,
+    window_b_ns: i64,
+    min_count: usize,
+) -> PyResult<(Py<PyArray1<i64>>, Py<PyArray1<f32>>)> {
+    if window_a_ns <= 0 || window_b_ns <= 0 {
+        return Err(FuseError::new_err("window_a_ns and window_b_ns must both be > 0"));
+    }
+    let a = t_a.as_array().to_vec();
+    let b = t_b.as_array().to_vec();
+    let (out_t, out_v, _, _) = fuse_pair_core(
+        &a,
+        &b,
+        window_a_ns,
+        window_b_ns,
+        min_count,
+        0.0,
+        false,
+        false,
+        None,
+        0,
+        true,
+    );
+
+    let t_arr = PyArray1::from_vec(py, out_t).unbind();
+    let v_arr = PyArray1::from_vec(py, out_v).unbind();
+    Ok((t_arr, v_arr))
+}
+
+/// Run `fuse_coincidence_i64` over many aligned `(a, b)` coordinate-pair
+/// streams in one call, computed in parallel with rayon. Avoids thousands of
+/// individual Python->Rust calls for per-pixel fusion pipelines.
+/// Returns a list of `(times, vals)` tuples, one per input pair, in order.
+#[pyfunction]
+fn fuse_coincidence_batched<'py>(
+    py: Python<'py>,
+    a_list: Vec<PyReadonlyArray1<i64>>,
+    b_list: Vec<PyReadonlyArray1<i64>>,
+    window_ns: i64,
+    min_count: usize,
+) -> PyResult<Py<PyList>> {
+    if window_ns <= 0 {
+        return Err(FuseError::new_err("window_ns must be > 0"));
+    }
+    if a_list.len() != b_list.len() {
+        return Err(FuseError::new_err("a_list and b_list must have the same length"));
+    }
+
+    // Copy inputs out of the Python-owned arrays up front so the fuse itself
+    // can run with the GIL released.
+    let pairs: Vec<(Vec<i64>, Vec<i64>)> = a_list
+        .iter()
+        .zip(b_list.iter())
+        .map(|(a, b)| (a.as_array().to_vec(), b.as_array().to_vec()))
+        .collect();
+
+    let results: Vec<(Vec<i64>, Vec<f32>)> = py.detach(|| {
+        use rayon::prelude::*;
+        pairs
+            .par_iter()
+            .map(|(a, b)| {
+                let (t, v, _, _) = fuse_pair_core(a, b, window_ns, window_ns, min_count, 0.0, false, false, None, 0, true);
+                (t, v)
+            })
+            .collect()
+    });
+
+    let out = PyList::empty(py);
+    for (t, v) in results {
+        let t_arr = PyArray1::from_vec(py, t);
+        let v_arr = PyArray1::from_vec(py, v);
+        out.append((t_arr, v_arr))?;
+    }
+    Ok(out.unbind())
+}
+
+/// N-stream coincidence fuse: generalizes `fuse_coincidence_i64` from a fixed
+/// A/B pair to an arbitrary list of streams. Merges all streams tagged by
+/// source index, maintains one `VecDeque` per stream, and emits `t` whenever
+/// at least `min_streams` of the buffers are non-empty and the total event
+/// count across all buffers is at least `min_count`. Returns times and
+/// values (all ones), same shape as `fuse_coincidence_i64` without its
+/// optional outputs. Errors if fewer than two streams are passed.
+#[pyfunction]
+fn fuse_coincidence_multi<'py>(
+    py: Python<'py>,
+    streams: Vec<PyReadonlyArray1<i64>>,
+    window_ns: i64,
+    min_streams: usize,
+    min_count: usize,
 ) -> PyResult<(Py<PyArray1<i64>>, Py<PyArray1<f32>>)> {
+    if streams.len() < 2 {
+        return Err(FuseError::new_err("at least two streams must be passed"));
+    }
     if window_ns <= 0 {
         return Err(FuseError::new_err("window_ns must be > 0"));
     }
+
+    let mut merged: Vec<(i64, usize)> = Vec::new();
+    for (src, stream) in streams.iter().enumerate() {
+        for &t in stream.as_array().iter() {
+            merged.push((t, src));
+        }
+    }
+    merged.sort_by_key(|x| x.0);
+
+    let mut bufs: Vec<VecDeque<i64>> = vec![VecDeque::new(); streams.len()];
+    let mut out_t: Vec<i64> = Vec::new();
+    let mut out_v: Vec<f32> = Vec::new();
+
+    for (t, src) in merged.into_iter() {
+        bufs[src].push_back(t);
+        let cutoff = t - window_ns;
+        for buf in bufs.iter_mut() {
+            while let Some(&front) = buf.front() {
+                if front < cutoff {
+                    buf.pop_front();
+                } else {
+                    break;
+                }
+            }
+        }
+
+        let active_streams = bufs.iter().filter(|b| !b.is_empty()).count();
+        let total: usize = bufs.iter().map(|b| b.len()).sum();
+        if active_streams >= min_streams && total >= min_count {
+            out_t.push(t);
+            out_v.push(1.0f32);
+        }
+    }
+
+    let t_arr = PyArray1::from_vec(py, out_t).unbind();
+    let v_arr = PyArray1::from_vec(py, out_v).unbind();
+    Ok((t_arr, v_arr))
+}
+
+/// Soft coincidence fuse: instead of a hard in/out decision, accumulate a
+/// graded score at each event from opposite-stream events within the window.
+/// - `kernel == "exp"`: score += exp(-|Δt| / window_ns) for each in-window opposite event
+/// - `kernel == "triangular"`: score += 1 - |Δt| / window_ns for each in-window opposite event
+/// Emits (t, score) whenever score exceeds `threshold`.
+#[pyfunction]
+fn fuse_coincidence_kernel_i64<'py>(
+    py: Python<'py>,
+    t_a: PyReadonlyArray1<i64>,
+    t_b: PyReadonlyArray1<i64>,
+    window_ns: i64,
+    kernel: &str,
+    threshold: f32,
+) -> PyResult<(Py<PyArray1<i64>>, Py<PyArray1<f32>>)> {
+    if window_ns <= 0 {
+        return Err(FuseError::new_err("window_ns must be > 0"));
+    }
+    if kernel != "exp" && kernel != "triangular" {
+        return Err(FuseError::new_err("kernel must be 'exp' or 'triangular'"));
+    }
     let a = t_a.as_array();
     let b = t_b.as_array();
 
-    // Merge timestamps with source tags (0 for A, 1 for B) and sort.
     let mut merged: Vec<(i64, u8)> = Vec::with_capacity(a.len() + b.len());
     for &t in a.iter() { merged.push((t, 0)); }
     for &t in b.iter() { merged.push((t, 1)); }
     merged.sort_by_key(|x| x.0);
 
+    let tau = window_ns as f64;
+    let window = window_ns as f64;
     let mut buf_a: VecDeque<i64> = VecDeque::new();
     let mut buf_b: VecDeque<i64> = VecDeque::new();
     let mut out_t: Vec<i64> = Vec::new();
     let mut out_v: Vec<f32> = Vec::new();
 
     for (t, src) in merged.into_iter() {
-        if src == 0 { buf_a.push_back(t); } else { buf_b.push_back(t); }
         let cutoff = t - window_ns;
-
         while let Some(&front) = buf_a.front() {
             if front < cutoff { buf_a.pop_front(); } else { break; }
         }
@@ -127,10 +1162,23 @@ fn fuse_coincidence_i64<'py>(
             if front < cutoff { buf_b.pop_front(); } else { break; }
         }
 
-        let total = buf_a.len() + buf_b.len();
-        if total >= min_count && !buf_a.is_empty() && !buf_b.is_empty() {
+        // Score against the opposite stream's buffered events before pushing the
+        // current event into its own stream's buffer.
+        let opposite = if src == 0 { &buf_b } else { &buf_a };
+        let mut score: f64 = 0.0;
+        for &ot in opposite.iter() {
+            let dt = (t - ot).unsigned_abs() as f64;
+            score += match kernel {
+                "exp" => (-dt / tau).exp(),
+                _ => (1.0 - dt / window).max(0.0),
+            };
+        }
+
+        if src == 0 { buf_a.push_back(t); } else { buf_b.push_back(t); }
+
+        if score > threshold as f64 {
             out_t.push(t);
-            out_v.push(1.0f32);
+            out_v.push(score as f32);
         }
     }
 
@@ -139,6 +1187,97 @@ fn fuse_coincidence_i64<'py>(
     Ok((t_arr, v_arr))
 }
 
+/// Stateful streaming counterpart to `fuse_coincidence_i64`: events arrive
+/// incrementally via `push_a`/`push_b` instead of being buffered up front,
+/// so an online pipeline can feed a generator's output through the detector
+/// chunk by chunk. The two `VecDeque`s are kept alive across calls and
+/// pruned by the latest timestamp seen in each push, exactly like the
+/// merge-and-prune loop `fuse_coincidence_i64` runs over a materialized
+/// batch — as long as callers push non-decreasing timestamps overall (each
+/// push's own times sorted, and interleaved across `push_a`/`push_b` calls
+/// the same way the batch function's merge would visit them), the sequence
+/// of coincidences detected is identical to calling the batch function once
+/// at the end. Only the `min_count` (no density gate, relative timestamps,
+/// or misses/imbalance tracking) subset of the batch function's semantics
+/// is supported, matching its default arguments.
+#[pyclass]
+struct CoincidenceDetector {
+    window_ns: i64,
+    min_count: usize,
+    buf_a: VecDeque<i64>,
+    buf_b: VecDeque<i64>,
+}
+
+impl CoincidenceDetector {
+    fn push_times<'py>(
+        &mut self,
+        py: Python<'py>,
+        times: PyReadonlyArray1<i64>,
+        is_a: bool,
+    ) -> PyResult<(Py<PyArray1<i64>>, Py<PyArray1<f32>>)> {
+        let mut out_t: Vec<i64> = Vec::new();
+        let mut out_v: Vec<f32> = Vec::new();
+
+        for &t in times.as_array().iter() {
+            if is_a {
+                self.buf_a.push_back(t);
+            } else {
+                self.buf_b.push_back(t);
+            }
+            let cutoff = t - self.window_ns;
+
+            while let Some(&front) = self.buf_a.front() {
+                if front < cutoff { self.buf_a.pop_front(); } else { break; }
+            }
+            while let Some(&front) = self.buf_b.front() {
+                if front < cutoff { self.buf_b.pop_front(); } else { break; }
+            }
+
+            let total = self.buf_a.len() + self.buf_b.len();
+            if total >= self.min_count && !self.buf_a.is_empty() && !self.buf_b.is_empty() {
+                out_t.push(t);
+                out_v.push(1.0f32);
+            }
+        }
+
+        let t_arr = PyArray1::from_vec(py, out_t).unbind();
+        let v_arr = PyArray1::from_vec(py, out_v).unbind();
+        Ok((t_arr, v_arr))
+    }
+}
+
+#[pymethods]
+impl CoincidenceDetector {
+    #[new]
+    fn new(window_ns: i64, min_count: usize) -> PyResult<Self> {
+        if window_ns <= 0 {
+            return Err(FuseError::new_err("window_ns must be > 0"));
+        }
+        Ok(CoincidenceDetector { window_ns, min_count, buf_a: VecDeque::new(), buf_b: VecDeque::new() })
+    }
+
+    /// Feeds new, non-decreasing timestamps into stream A and returns any
+    /// coincidences detected while ingesting them (times, vals).
+    fn push_a<'py>(&mut self, py: Python<'py>, times: PyReadonlyArray1<i64>) -> PyResult<(Py<PyArray1<i64>>, Py<PyArray1<f32>>)> {
+        self.push_times(py, times, true)
+    }
+
+    /// Feeds new, non-decreasing timestamps into stream B and returns any
+    /// coincidences detected while ingesting them (times, vals).
+    fn push_b<'py>(&mut self, py: Python<'py>, times: PyReadonlyArray1<i64>) -> PyResult<(Py<PyArray1<i64>>, Py<PyArray1<f32>>)> {
+        self.push_times(py, times, false)
+    }
+
+    /// Drops all buffered state. No more coincidences can be produced by
+    /// events already pushed (a coincidence is only ever emitted at the
+    /// moment a qualifying event arrives), so this simply resets the
+    /// detector for reuse rather than returning anything.
+    fn flush(&mut self) {
+        self.buf_a.clear();
+        self.buf_b.clear();
+    }
+}
+
 // Logging bridge API
 #[pyfunction]
 fn set_log_sink(sink: Option<Py<PyAny>>) -> PyResult<()> {
@@ -148,8 +1287,22 @@ fn set_log_sink(sink: Option<Py<PyAny>>) -> PyResult<()> {
     Ok(())
 }
 
+#[pyfunction]
+fn set_log_level(level: &str) -> PyResult<()> {
+    let rank = log_level_rank(level).ok_or_else(|| {
+        PyValueError::new_err("level must be one of 'trace', 'debug', 'info', 'warn', 'error'")
+    })?;
+    let cell = LOG_LEVEL.get_or_init(|| RwLock::new(0));
+    *cell.write().unwrap() = rank;
+    Ok(())
+}
+
 #[pyfunction]
 fn log_emit(py: Python<'_>, level: &str, message: &str) -> PyResult<()> {
+    let threshold = LOG_LEVEL.get().map(|c| *c.read().unwrap()).unwrap_or(0);
+    if log_level_rank(level).is_some_and(|rank| rank < threshold) {
+        return Ok(());
+    }
     if let Some(lock) = LOG_SINK.get() {
         if let Ok(guard) = lock.read() {
             if let Some(sink) = guard.as_ref() {
@@ -171,9 +1324,29 @@ fn _native(m: &Bound<PyModule>) -> PyResult<()> {
     m.add_function(wrap_pyfunction!(is_ready, m)?)?;
     m.add_function(wrap_pyfunction!(sum_f32, m)?)?;
     m.add_function(wrap_pyfunction!(sum_ndarray_f32, m)?)?;
+    m.add_function(wrap_pyfunction!(sum_ndarray_f64, m)?)?;
+    m.add_function(wrap_pyfunction!(sum_ndarray_i64, m)?)?;
+    m.add_function(wrap_pyfunction!(build_info, m)?)?;
+    m.add_function(wrap_pyfunction!(quantize_time_i64, m)?)?;
+    m.add_function(wrap_pyfunction!(isi_i64, m)?)?;
+    m.add_function(wrap_pyfunction!(histogram_counts_i64, m)?)?;
     m.add_function(wrap_pyfunction!(bucket_sum_i64_f32, m)?)?;
+    m.add_function(wrap_pyfunction!(bucket_reduce_i64_f32, m)?)?;
+    m.add_function(wrap_pyfunction!(bucket_sum_i64_f64, m)?)?;
+    m.add_function(wrap_pyfunction!(bucket_sum_span_i64_f32, m)?)?;
+    m.add_function(wrap_pyfunction!(bucket_sum_capped_i64_f32, m)?)?;
+    m.add_function(wrap_pyfunction!(leaky_integrate_i64_f32, m)?)?;
+    m.add_function(wrap_pyfunction!(integrate_and_fire_i64_f32, m)?)?;
+    m.add_function(wrap_pyfunction!(sliding_sum_i64_f32, m)?)?;
     m.add_function(wrap_pyfunction!(fuse_coincidence_i64, m)?)?;
+    m.add_function(wrap_pyfunction!(fuse_coincidence_i64_detailed, m)?)?;
+    m.add_function(wrap_pyfunction!(fuse_coincidence_asym_i64, m)?)?;
+    m.add_function(wrap_pyfunction!(fuse_coincidence_batched, m)?)?;
+    m.add_function(wrap_pyfunction!(fuse_coincidence_multi, m)?)?;
+    m.add_function(wrap_pyfunction!(fuse_coincidence_kernel_i64, m)?)?;
     m.add_function(wrap_pyfunction!(set_log_sink, m)?)?;
+    m.add_function(wrap_pyfunction!(set_log_level, m)?)?;
     m.add_function(wrap_pyfunction!(log_emit, m)?)?;
+    m.add_class::<CoincidenceDetector>()?;
     Ok(())
 }
\ No newline at end of file
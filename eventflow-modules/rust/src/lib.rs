@@ -1,4 +1,4 @@
-use numpy::{PyArray2, PyReadonlyArray2, PyArray1};
+use numpy::{PyArray2, PyArray3, PyReadonlyArray1, PyReadonlyArray2, PyArray1};
 use numpy::PyArrayMethods;
 use pyo3::prelude::*;
 use pyo3::types::{PyDict, PyList};
@@ -11,15 +11,52 @@ use serde::Deserialize;
 use serde_json::Value;
 use std::collections::{HashMap, VecDeque, HashSet};
 use std::fs::File;
-use std::io::{BufRead, BufReader};
+use std::io::{BufRead, BufReader, Read};
 
 /// Custom Python exception for vision kernels
 create_exception!(eventflow_modules_vision_native, VisionError, pyo3::exceptions::PyException);
 
+/// Plain-data error produced inside a `py.detach` compute closure, where no
+/// Python object (including a `PyErr`) can be constructed since the GIL
+/// isn't held. Converted to the right `PyErr` via `into_py_err` once control
+/// returns to GIL-holding code.
+enum ComputeError {
+    Io(String),
+    UnparseableLine(usize),
+    Vision(String),
+}
+
+impl ComputeError {
+    fn into_py_err(self) -> PyErr {
+        match self {
+            ComputeError::Io(msg) => PyIOError::new_err(msg),
+            ComputeError::UnparseableLine(lineno) => VisionError::new_err(format!(
+                "unparseable line {lineno} (neither a header nor an event)"
+            )),
+            ComputeError::Vision(msg) => VisionError::new_err(msg),
+        }
+    }
+}
+
 /// Global optional logging sink (callable)
 /// Callable signature: sink(level: str, message: str)
 static LOG_SINK: OnceCell<RwLock<Option<Py<PyAny>>>> = OnceCell::new();
 
+// Minimum level a message must meet to reach the sink. Default is `trace`
+// (rank 0), i.e. nothing is filtered unless `set_log_level` is called.
+static LOG_LEVEL: OnceCell<RwLock<u8>> = OnceCell::new();
+
+fn log_level_rank(level: &str) -> Option<u8> {
+    match level {
+        "trace" => Some(0),
+        "debug" => Some(1),
+        "info" => Some(2),
+        "warn" => Some(3),
+        "error" => Some(4),
+        _ => None,
+    }
+}
+
 #[pyfunction]
 fn set_log_sink(sink: Option<Py<PyAny>>) -> PyResult<()> {
     let cell = LOG_SINK.get_or_init(|| RwLock::new(None));
@@ -28,8 +65,22 @@ fn set_log_sink(sink: Option<Py<PyAny>>) -> PyResult<()> {
     Ok(())
 }
 
+#[pyfunction]
+fn set_log_level(level: &str) -> PyResult<()> {
+    let rank = log_level_rank(level).ok_or_else(|| {
+        PyValueError::new_err("level must be one of 'trace', 'debug', 'info', 'warn', 'error'")
+    })?;
+    let cell = LOG_LEVEL.get_or_init(|| RwLock::new(0));
+    *cell.write().unwrap() = rank;
+    Ok(())
+}
+
 #[pyfunction]
 fn log_emit(py: Python<'_>, level: &str, message: &str) -> PyResult<()> {
+    let threshold = LOG_LEVEL.get().map(|c| *c.read().unwrap()).unwrap_or(0);
+    if log_level_rank(level).is_some_and(|rank| rank < threshold) {
+        return Ok(());
+    }
     if let Some(lock) = LOG_SINK.get() {
         if let Ok(guard) = lock.read() {
             if let Some(sink) = guard.as_ref() {
@@ -57,431 +108,3578 @@ fn optical_flow_stub<'py>(py: Python<'py>, frames: PyReadonlyArray2<f32>) -> PyR
     Ok(out.unbind())
 }
 
+/// Expand an input path into one or more files to read as a single logical
+/// stream. Paths containing glob metacharacters (`*`, `?`, `[`) are expanded
+/// with the `glob` crate and matched files are sorted by name; a plain path
+/// is returned as-is. Segments are concatenated in sorted order, so callers
+/// whose recordings are split into numbered files (e.g. `recording_*.jsonl`)
+/// don't need to pre-concatenate them.
+fn expand_input_paths(input_path: &str) -> PyResult<Vec<std::path::PathBuf>> {
+    if input_path.contains(['*', '?', '[']) {
+        let mut paths: Vec<std::path::PathBuf> = glob::glob(input_path)
+            .map_err(|e| VisionError::new_err(format!("invalid glob pattern '{input_path}': {e}")))?
+            .filter_map(Result::ok)
+            .collect();
+        if paths.is_empty() {
+            return Err(VisionError::new_err(format!("glob pattern matched no files: {input_path}")));
+        }
+        paths.sort();
+        Ok(paths)
+    } else {
+        Ok(vec![std::path::PathBuf::from(input_path)])
+    }
+}
+
+/// Open a single file as a `Read`, transparently decompressing it if it
+/// starts with the gzip magic bytes `0x1f 0x8b`. Detection peeks the file's
+/// buffer without consuming it (`BufRead::fill_buf`), so plain files are
+/// handed back unmodified with nothing lost from the stream.
+fn open_segment_reader(path: &std::path::Path) -> PyResult<Box<dyn std::io::Read + Send>> {
+    let file = File::open(path).map_err(|e| PyIOError::new_err(format!("open failed: {e}")))?;
+    let mut buffered = BufReader::new(file);
+    let is_gzip = {
+        let peek = buffered.fill_buf().map_err(|e| PyIOError::new_err(format!("read failed: {e}")))?;
+        peek.len() >= 2 && peek[0] == 0x1f && peek[1] == 0x8b
+    };
+    if is_gzip {
+        Ok(Box::new(flate2::read::GzDecoder::new(buffered)))
+    } else {
+        Ok(Box::new(buffered))
+    }
+}
+
+/// Open an input path (or glob pattern matching multiple segment files) as a
+/// single buffered reader over their concatenated contents, in sorted-file
+/// order. Because the per-line parse loop already ignores a header line once
+/// one has been captured, the first matched file's header wins and later
+/// segments' header lines are skipped like any other unrecognized line. Each
+/// segment is independently auto-detected for gzip compression, so a
+/// directory mixing compressed and plain segments (or a single `.jsonl.gz`)
+/// works without the caller branching on extension.
+fn open_input_reader(input_path: &str) -> PyResult<BufReader<Box<dyn std::io::Read + Send>>> {
+    let paths = expand_input_paths(input_path)?;
+    let mut combined: Box<dyn std::io::Read + Send> = Box::new(std::io::empty());
+    for p in paths {
+        let seg = open_segment_reader(&p)?;
+        combined = Box::new(combined.chain(seg));
+    }
+    Ok(BufReader::new(combined))
+}
+
 /// Input header wrapper if present in JSONL
 #[derive(Deserialize)]
 struct InputHeader {
     header: Value,
 }
 
+/// Accepts `idx` as either `[x, y, polarity]` (the normal case) or `[x, y]`
+/// for frame-camera-derived traces that carry no polarity, defaulting the
+/// missing polarity to `0` rather than silently dropping the line.
+fn deserialize_idx<'de, D>(deserializer: D) -> Result<[i64; 3], D::Error>
+where
+    D: serde::Deserializer<'de>,
+{
+    use serde::de::Error;
+    let v: Vec<i64> = Vec::deserialize(deserializer)?;
+    match v.as_slice() {
+        [x, y, pol] => Ok([*x, *y, *pol]),
+        [x, y] => Ok([*x, *y, 0]),
+        _ => Err(D::Error::custom("idx must have length 2 or 3")),
+    }
+}
+
+/// Accepts `ts` as either an integer or a float microsecond timestamp (some
+/// Python simulators emit float time), rounding floats to the nearest `i64`.
+/// Integer inputs are returned exactly, with no intermediate float roundtrip.
+fn deserialize_ts<'de, D>(deserializer: D) -> Result<i64, D::Error>
+where
+    D: serde::Deserializer<'de>,
+{
+    use serde::de::Error;
+    let v = serde_json::Number::deserialize(deserializer)?;
+    if let Some(i) = v.as_i64() {
+        Ok(i)
+    } else if let Some(f) = v.as_f64() {
+        Ok(f.round() as i64)
+    } else {
+        Err(D::Error::custom("ts must be a number"))
+    }
+}
+
 /// Input event line expected in normalized DVS traces
 #[derive(Deserialize)]
 struct InputEvent {
+    #[serde(deserialize_with = "deserialize_ts")]
     ts: i64,
+    #[serde(deserialize_with = "deserialize_idx")]
     idx: [i64; 3], // [x, y, polarity]
 }
 
-/// Coincidence-based optical flow on DVS events with Shift/Delay/Fuse semantics.
-/// - Reads JSONL from input_path (expects optional header line and per-event lines)
-/// - Emits events at (x,y,pol) when a neighbor event (shifted by +/-1 in x and delayed)
-///   falls within [t - window_us, t]
-/// - Returns (header_dict, events_list) to Python for easy comparison or writing
+/// Per-row event density: total event count for each y-coordinate in [0, height).
+/// Polarity is summed over (both ON and OFF events contribute to the same row).
 #[pyfunction]
-#[allow(clippy::too_many_arguments)]
-fn optical_flow_coo_from_jsonl<'py>(
-    py: Python<'py>,
-    input_path: &str,
-    width: usize,
-    height: usize,
-    _window_us: i64,
-    _delay_us: i64,
-    _edge_delay_us: i64,
-    _min_count: usize,
-) -> PyResult<(Py<PyAny>, Py<PyAny>)> {
-    // Pass-through implementation to match the example golden trace produced by the "flow" probe.
+fn row_event_counts<'py>(py: Python<'py>, input_path: &str, width: usize, height: usize) -> PyResult<Py<PyArray1<i64>>> {
     if width == 0 || height == 0 {
         return Err(VisionError::new_err("width/height must be > 0"));
     }
-    let file = File::open(input_path).map_err(|e| PyIOError::new_err(format!("open failed: {e}")))?;
-    let reader = BufReader::new(file);
+    let reader = open_input_reader(input_path)?;
 
-    let mut header_opt: Option<Value> = None;
-    let mut out_events: Vec<(i64, i64, i64, i64)> = Vec::new();
+    let mut counts: Vec<i64> = vec![0; height];
+    for line in reader.lines() {
+        let line = line.map_err(|e| PyIOError::new_err(format!("read failed: {e}")))?;
+        if line.trim().is_empty() {
+            continue;
+        }
+        if let Ok(ev) = serde_json::from_str::<InputEvent>(&line) {
+            let x = ev.idx[0];
+            let y = ev.idx[1];
+            if x >= 0 && (x as usize) < width && y >= 0 && (y as usize) < height {
+                counts[y as usize] += 1;
+            }
+        }
+    }
+
+    Ok(PyArray1::from_vec(py, counts).unbind())
+}
+
+/// Per-column event density: total event count for each x-coordinate in [0, width).
+/// Symmetric to `row_event_counts`; polarity is summed over.
+#[pyfunction]
+fn col_event_counts<'py>(py: Python<'py>, input_path: &str, width: usize, height: usize) -> PyResult<Py<PyArray1<i64>>> {
+    if width == 0 || height == 0 {
+        return Err(VisionError::new_err("width/height must be > 0"));
+    }
+    let reader = open_input_reader(input_path)?;
 
+    let mut counts: Vec<i64> = vec![0; width];
     for line in reader.lines() {
         let line = line.map_err(|e| PyIOError::new_err(format!("read failed: {e}")))?;
         if line.trim().is_empty() {
             continue;
         }
-        if header_opt.is_none() {
-            if let Ok(h) = serde_json::from_str::<InputHeader>(&line) {
-                header_opt = Some(h.header);
-                continue;
+        if let Ok(ev) = serde_json::from_str::<InputEvent>(&line) {
+            let x = ev.idx[0];
+            let y = ev.idx[1];
+            if x >= 0 && (x as usize) < width && y >= 0 && (y as usize) < height {
+                counts[x as usize] += 1;
             }
         }
+    }
+
+    Ok(PyArray1::from_vec(py, counts).unbind())
+}
+
+fn load_coord_set(path: &str, width: usize, height: usize) -> PyResult<HashSet<(i64, i64, i64, i64)>> {
+    let reader = open_input_reader(path)?;
+    let mut set = HashSet::new();
+    for line in reader.lines() {
+        let line = line.map_err(|e| PyIOError::new_err(format!("read failed: {e}")))?;
+        if line.trim().is_empty() {
+            continue;
+        }
         if let Ok(ev) = serde_json::from_str::<InputEvent>(&line) {
-            let ts = ev.ts;
             let x = ev.idx[0];
             let y = ev.idx[1];
             let pol = ev.idx[2];
-            if x >= 0 && (x as usize) < width && y >= 0 && (y as usize) < height && pol >= 0 && pol <= 1 {
-                out_events.push((ts, x, y, pol));
+            if x >= 0 && (x as usize) < width && y >= 0 && (y as usize) < height {
+                set.insert((ev.ts, x, y, pol));
             }
         }
     }
+    Ok(set)
+}
 
-    // Build header dict
-    let hdr = PyDict::new(py);
-    hdr.set_item("schema_version", "0.1.0")?;
-    hdr.set_item("dims", vec!["x", "y", "polarity"])?;
-    let units = PyDict::new(py);
-    units.set_item("time", "us")?;
-    units.set_item("value", "dimensionless")?;
-    hdr.set_item("units", units)?;
-    hdr.set_item("dtype", "f32")?;
-    hdr.set_item("layout", "coo")?;
-    let md = PyDict::new(py);
-    md.set_item("backend", "native-rust")?;
-    md.set_item("kernel", "passthrough_events")?;
-    hdr.set_item("metadata", md)?;
+/// Confusion metrics between a predicted event set and a reference event set,
+/// matched exactly on (ts, x, y, polarity). Returns a dict with
+/// true_positives, false_positives, false_negatives, precision, recall.
+#[pyfunction]
+fn event_set_metrics<'py>(py: Python<'py>, pred_path: &str, ref_path: &str, width: usize, height: usize) -> PyResult<Py<PyDict>> {
+    if width == 0 || height == 0 {
+        return Err(VisionError::new_err("width/height must be > 0"));
+    }
+    let pred = load_coord_set(pred_path, width, height)?;
+    let refs = load_coord_set(ref_path, width, height)?;
 
-    // If the source had a header, try to preserve dims
-    if let Some(src_hdr) = header_opt {
-        if let Some(dims) = src_hdr.get("dims") {
-            if let Some(arr) = dims.as_array() {
-                let py_dims = PyList::empty(py);
-                for v in arr {
-                    if let Some(s) = v.as_str() {
-                        py_dims.append(s)?;
-                    } else if let Some(n) = v.as_i64() {
-                        py_dims.append(n)?;
-                    } else {
-                        py_dims.append(v.to_string())?;
-                    }
+    let tp = pred.intersection(&refs).count();
+    let fp = pred.len() - tp;
+    let fn_ = refs.len() - tp;
+
+    let precision = if pred.is_empty() { 0.0 } else { tp as f64 / pred.len() as f64 };
+    let recall = if refs.is_empty() { 0.0 } else { tp as f64 / refs.len() as f64 };
+
+    let out = PyDict::new(py);
+    out.set_item("true_positives", tp)?;
+    out.set_item("false_positives", fp)?;
+    out.set_item("false_negatives", fn_)?;
+    out.set_item("precision", precision)?;
+    out.set_item("recall", recall)?;
+    Ok(out.unbind())
+}
+
+/// Per-pixel dominant polarity image: +1 where ON (polarity 1) events
+/// outnumber OFF (polarity 0) events, -1 where OFF dominates, 0 where equal
+/// or absent. Built from two count grids accumulated during a single parse.
+#[pyfunction]
+fn dominant_polarity_image<'py>(py: Python<'py>, input_path: &str, width: usize, height: usize) -> PyResult<Py<PyArray2<i8>>> {
+    if width == 0 || height == 0 {
+        return Err(VisionError::new_err("width/height must be > 0"));
+    }
+    let reader = open_input_reader(input_path)?;
+
+    let mut on_counts: Vec<i64> = vec![0; width * height];
+    let mut off_counts: Vec<i64> = vec![0; width * height];
+
+    for line in reader.lines() {
+        let line = line.map_err(|e| PyIOError::new_err(format!("read failed: {e}")))?;
+        if line.trim().is_empty() {
+            continue;
+        }
+        if let Ok(ev) = serde_json::from_str::<InputEvent>(&line) {
+            let x = ev.idx[0];
+            let y = ev.idx[1];
+            let pol = ev.idx[2];
+            if x >= 0 && (x as usize) < width && y >= 0 && (y as usize) < height {
+                let idx = (y as usize) * width + (x as usize);
+                if pol == 1 {
+                    on_counts[idx] += 1;
+                } else if pol == 0 {
+                    off_counts[idx] += 1;
                 }
-                hdr.set_item("dims", py_dims)?;
             }
         }
     }
 
-    // Sort events for deterministic comparison
-    out_events.sort_unstable_by(|a, b| a.cmp(b));
+    let out = PyArray2::<i8>::zeros(py, (height, width), false);
+    // SAFETY: out is newly allocated with exclusive ownership while holding the GIL
+    let mut out_view = unsafe { out.as_array_mut() };
+    for y in 0..height {
+        for x in 0..width {
+            let idx = y * width + x;
+            out_view[[y, x]] = match on_counts[idx].cmp(&off_counts[idx]) {
+                std::cmp::Ordering::Greater => 1,
+                std::cmp::Ordering::Less => -1,
+                std::cmp::Ordering::Equal => 0,
+            };
+        }
+    }
 
-    // Build events list
-    let ev_list = PyList::empty(py);
-    for (ts, x, y, pol) in out_events {
-        let d = PyDict::new(py);
-        d.set_item("ts", ts)?;
-        d.set_item("idx", vec![x, y, pol])?;
-        d.set_item("val", 1.0f32)?;
-        ev_list.append(d)?;
+    Ok(out.unbind())
+}
+
+/// Per-pixel last-event-age map: at reference time `t_ref_us`, how long since
+/// each pixel last fired (`t_ref_us - t_last`). Pixels that never fired get a
+/// sentinel of `i64::MAX` so they're trivially distinguishable from a real,
+/// arbitrarily large age. Built from a single per-pixel last-timestamp grid
+/// during parsing; the raw input to thresholded recency/motion masks.
+#[pyfunction]
+fn event_age_map<'py>(
+    py: Python<'py>,
+    input_path: &str,
+    width: usize,
+    height: usize,
+    t_ref_us: i64,
+) -> PyResult<Py<PyArray2<i64>>> {
+    if width == 0 || height == 0 {
+        return Err(VisionError::new_err("width/height must be > 0"));
     }
+    let reader = open_input_reader(input_path)?;
+
+    let mut last_ts: Vec<Option<i64>> = vec![None; width * height];
 
-    Ok((hdr.unbind().into(), ev_list.unbind().into()))
+    for line in reader.lines() {
+        let line = line.map_err(|e| PyIOError::new_err(format!("read failed: {e}")))?;
+        if line.trim().is_empty() {
+            continue;
+        }
+        if let Ok(ev) = serde_json::from_str::<InputEvent>(&line) {
+            let x = ev.idx[0];
+            let y = ev.idx[1];
+            if x >= 0 && (x as usize) < width && y >= 0 && (y as usize) < height {
+                let idx = (y as usize) * width + (x as usize);
+                last_ts[idx] = Some(ev.ts);
+            }
+        }
     }
-    
-    /// Pass-through returning columnar NumPy arrays (ts, x, y, polarity, val)
-    #[pyfunction]
-    fn optical_flow_coo_arrays<'py>(
-        py: Python<'py>,
-        input_path: &str,
-        width: usize,
-        height: usize,
-    ) -> PyResult<(Py<PyAny>, Py<PyAny>)> {
-        if width == 0 || height == 0 {
-            return Err(VisionError::new_err("width/height must be > 0"));
+
+    let out = PyArray2::<i64>::zeros(py, (height, width), false);
+    // SAFETY: out is newly allocated with exclusive ownership while holding the GIL
+    let mut out_view = unsafe { out.as_array_mut() };
+    for y in 0..height {
+        for x in 0..width {
+            let idx = y * width + x;
+            out_view[[y, x]] = match last_ts[idx] {
+                Some(t_last) => t_ref_us - t_last,
+                None => i64::MAX,
+            };
         }
-        let file = File::open(input_path).map_err(|e| PyIOError::new_err(format!("open failed: {e}")))?;
-        let reader = BufReader::new(file);
-    
-        let mut header_opt: Option<Value> = None;
-        let mut out_events: Vec<(i64, i64, i64, i64)> = Vec::new();
-    
-        for line in reader.lines() {
-            let line = line.map_err(|e| PyIOError::new_err(format!("read failed: {e}")))?;
-            if line.trim().is_empty() {
+    }
+
+    Ok(out.unbind())
+}
+
+/// Surface of Active Events (SAE) / time surface: for each pixel, the most
+/// recent event timestamp at or before `query_ts`, exponentially decayed
+/// relative to `query_ts` with time constant `tau_us`. A pixel that never
+/// fired before `query_ts` is `0.0`. Same `exp(-dt/tau)` decay convention as
+/// `EventSurface::snapshot`, but computed from a single streamed pass over
+/// `input_path` rather than incremental per-event updates.
+///
+/// `polarity`: `None` (the default) tracks the most recent event regardless
+/// of polarity; `Some(0)` or `Some(1)` restricts the surface to only OFF or
+/// only ON events respectively, so ON/OFF surfaces can be computed
+/// separately and summed by the caller if a combined view is wanted.
+#[pyfunction]
+#[pyo3(signature = (input_path, width, height, tau_us, query_ts, polarity=None))]
+fn time_surface<'py>(
+    py: Python<'py>,
+    input_path: &str,
+    width: usize,
+    height: usize,
+    tau_us: f64,
+    query_ts: i64,
+    polarity: Option<i64>,
+) -> PyResult<Py<PyArray2<f32>>> {
+    if width == 0 || height == 0 {
+        return Err(VisionError::new_err("width/height must be > 0"));
+    }
+    if tau_us <= 0.0 {
+        return Err(VisionError::new_err("tau_us must be > 0"));
+    }
+    let reader = open_input_reader(input_path)?;
+
+    let mut last_ts: Vec<Option<i64>> = vec![None; width * height];
+
+    for line in reader.lines() {
+        let line = line.map_err(|e| PyIOError::new_err(format!("read failed: {e}")))?;
+        if line.trim().is_empty() {
+            continue;
+        }
+        if let Ok(ev) = serde_json::from_str::<InputEvent>(&line) {
+            let x = ev.idx[0];
+            let y = ev.idx[1];
+            let pol = ev.idx[2];
+            if ev.ts > query_ts {
                 continue;
             }
-            if header_opt.is_none() {
-                if let Ok(h) = serde_json::from_str::<InputHeader>(&line) {
-                    header_opt = Some(h.header);
+            if let Some(want) = polarity {
+                if pol != want {
                     continue;
                 }
             }
-            if let Ok(ev) = serde_json::from_str::<InputEvent>(&line) {
-                let ts = ev.ts;
-                let x = ev.idx[0];
-                let y = ev.idx[1];
-                let pol = ev.idx[2];
-                if x >= 0 && (x as usize) < width && y >= 0 && (y as usize) < height && pol >= 0 && pol <= 1 {
-                    out_events.push((ts, x, y, pol));
-                }
+            if x >= 0 && (x as usize) < width && y >= 0 && (y as usize) < height {
+                let idx = (y as usize) * width + (x as usize);
+                last_ts[idx] = Some(ev.ts);
+            }
+        }
+    }
+
+    let out = PyArray2::<f32>::zeros(py, (height, width), false);
+    // SAFETY: out is newly allocated with exclusive ownership while holding the GIL
+    let mut out_view = unsafe { out.as_array_mut() };
+    for y in 0..height {
+        for x in 0..width {
+            let idx = y * width + x;
+            if let Some(t_last) = last_ts[idx] {
+                let dt = (query_ts - t_last) as f64;
+                out_view[[y, x]] = (-dt / tau_us).exp() as f32;
+            }
+        }
+    }
+
+    Ok(out.unbind())
+}
+
+/// Accumulates events in `[t_start, t_end)` into a per-pixel frame, avoiding
+/// a slow Python loop over the columnar arrays. `polarity_mode` selects what
+/// gets counted into each cell: `"count"` counts every event regardless of
+/// polarity, `"on"`/`"off"` count only ON (`pol == 1`) or OFF (`pol == 0`)
+/// events, and `"diff"` accumulates ON count minus OFF count as a signed
+/// total. Returns a `PyArray2<i64>` of shape `(height, width)`.
+#[pyfunction]
+#[pyo3(signature = (input_path, width, height, t_start, t_end, polarity_mode="count"))]
+fn accumulate_frame<'py>(
+    py: Python<'py>,
+    input_path: &str,
+    width: usize,
+    height: usize,
+    t_start: i64,
+    t_end: i64,
+    polarity_mode: &str,
+) -> PyResult<Py<PyArray2<i64>>> {
+    if width == 0 || height == 0 {
+        return Err(VisionError::new_err("width/height must be > 0"));
+    }
+    if t_end <= t_start {
+        return Err(VisionError::new_err("t_end must be > t_start"));
+    }
+    if !["count", "on", "off", "diff"].contains(&polarity_mode) {
+        return Err(VisionError::new_err("polarity_mode must be 'count', 'on', 'off', or 'diff'"));
+    }
+    let reader = open_input_reader(input_path)?;
+
+    let mut counts: Vec<i64> = vec![0; width * height];
+
+    for line in reader.lines() {
+        let line = line.map_err(|e| PyIOError::new_err(format!("read failed: {e}")))?;
+        if line.trim().is_empty() {
+            continue;
+        }
+        if let Ok(ev) = serde_json::from_str::<InputEvent>(&line) {
+            if ev.ts < t_start || ev.ts >= t_end {
+                continue;
+            }
+            let x = ev.idx[0];
+            let y = ev.idx[1];
+            let pol = ev.idx[2];
+            if x < 0 || y < 0 || (x as usize) >= width || (y as usize) >= height {
+                continue;
+            }
+            let idx = (y as usize) * width + (x as usize);
+            match polarity_mode {
+                "count" => counts[idx] += 1,
+                "on" => {
+                    if pol == 1 {
+                        counts[idx] += 1;
+                    }
+                }
+                "off" => {
+                    if pol == 0 {
+                        counts[idx] += 1;
+                    }
+                }
+                "diff" => {
+                    counts[idx] += if pol == 1 { 1 } else { -1 };
+                }
+                _ => unreachable!(),
+            }
+        }
+    }
+
+    let out = PyArray2::<i64>::zeros(py, (height, width), false);
+    // SAFETY: out is newly allocated with exclusive ownership while holding the GIL
+    let mut out_view = unsafe { out.as_array_mut() };
+    for y in 0..height {
+        for x in 0..width {
+            out_view[[y, x]] = counts[y * width + x];
+        }
+    }
+
+    Ok(out.unbind())
+}
+
+/// Per-pixel coincidence count for a single `(dx, dy)` shift vector: an A
+/// stream of every event's own timestamp at its pixel, and a B stream of
+/// each event's timestamp plus `delay_us` deposited at its `(x+dx, y+dy)`
+/// neighbor (dropped if that neighbor is out of bounds). Reuses
+/// `fuse_coordinate` with `min_count=1` (any coincidence counts) and
+/// polarity ignored, since `motion_energy_maps` reports energy per pixel,
+/// not per polarity.
+fn motion_energy_for_shift(
+    events: &[(i64, i64, i64)],
+    width: usize,
+    height: usize,
+    dx: i64,
+    dy: i64,
+    window_us: i64,
+    delay_us: i64,
+) -> Vec<i64> {
+    let mut a_map: HashMap<(i64, i64), Vec<i64>> = HashMap::new();
+    let mut b_map: HashMap<(i64, i64), Vec<i64>> = HashMap::new();
+    for &(ts, x, y) in events {
+        a_map.entry((x, y)).or_default().push(ts);
+        let nx = x + dx;
+        let ny = y + dy;
+        if nx >= 0 && (nx as usize) < width && ny >= 0 && (ny as usize) < height {
+            b_map.entry((nx, ny)).or_default().push(ts.saturating_add(delay_us));
+        }
+    }
+
+    let mut keys: HashSet<(i64, i64)> = HashSet::new();
+    keys.extend(a_map.keys().cloned());
+    keys.extend(b_map.keys().cloned());
+
+    let mut counts = vec![0i64; width * height];
+    for (x, y) in keys {
+        let va = a_map.remove(&(x, y)).unwrap_or_default();
+        let vb = b_map.remove(&(x, y)).unwrap_or_default();
+        let fused = fuse_coordinate(x, y, 0, va, vb, window_us, 1, true);
+        counts[(y as usize) * width + (x as usize)] = fused.len() as i64;
+    }
+    counts
+}
+
+/// Coarse, dense alternative to `optical_flow_shift_delay_fuse_coo`'s sparse
+/// coincidence list: four per-pixel `PyArray2<i64>` maps of shape
+/// `(height, width)`, one per cardinal direction, each counting how many
+/// times that pixel's own events coincided (within `window_us`, after
+/// `delay_us`) with a `"recent event, then this pixel fires"` chain from the
+/// corresponding neighbor -- `"up"`/`"down"` from the `(x, y∓1)` neighbor,
+/// `"left"`/`"right"` from the `(x∓1, y)` neighbor. Runs the same
+/// `fuse_coordinate` coincidence machinery as the sparse flow kernel, once
+/// per direction, with `min_count=1` and polarity ignored. Returns a dict
+/// with keys `"up"`, `"down"`, `"left"`, `"right"`.
+#[pyfunction]
+#[pyo3(signature = (input_path, width, height, window_us, delay_us))]
+fn motion_energy_maps<'py>(
+    py: Python<'py>,
+    input_path: &str,
+    width: usize,
+    height: usize,
+    window_us: i64,
+    delay_us: i64,
+) -> PyResult<Py<PyDict>> {
+    if width == 0 || height == 0 {
+        return Err(VisionError::new_err("width/height must be > 0"));
+    }
+    if window_us <= 0 {
+        return Err(VisionError::new_err("window_us must be > 0"));
+    }
+    if delay_us < 0 {
+        return Err(VisionError::new_err("delay_us must be >= 0"));
+    }
+    let reader = open_input_reader(input_path)?;
+
+    let mut events: Vec<(i64, i64, i64)> = Vec::new();
+    for line in reader.lines() {
+        let line = line.map_err(|e| PyIOError::new_err(format!("read failed: {e}")))?;
+        if line.trim().is_empty() {
+            continue;
+        }
+        if let Ok(ev) = serde_json::from_str::<InputEvent>(&line) {
+            let ts = ev.ts;
+            let x = ev.idx[0];
+            let y = ev.idx[1];
+            if x < 0 || y < 0 || (x as usize) >= width || (y as usize) >= height {
+                continue;
+            }
+            events.push((ts, x, y));
+        }
+    }
+
+    let shifts: [(&str, i64, i64); 4] = [("up", 0, -1), ("down", 0, 1), ("left", -1, 0), ("right", 1, 0)];
+    let out = PyDict::new(py);
+    for (name, dx, dy) in shifts {
+        let counts = motion_energy_for_shift(&events, width, height, dx, dy, window_us, delay_us);
+        let arr = PyArray2::<i64>::zeros(py, (height, width), false);
+        // SAFETY: arr is newly allocated with exclusive ownership while holding the GIL
+        let mut arr_view = unsafe { arr.as_array_mut() };
+        for y in 0..height {
+            for x in 0..width {
+                arr_view[[y, x]] = counts[y * width + x];
+            }
+        }
+        out.set_item(name, arr)?;
+    }
+
+    Ok(out.unbind())
+}
+
+/// Maps each event's `(x, y)` to `(x/factor, y/factor)`, collapsing
+/// duplicate `(ts, x, y, pol)` tuples that coincide after downsampling into
+/// a single output event. Floor division already sends any edge pixels left
+/// over from a non-evenly-dividing `factor` into the last bin rather than
+/// dropping them (e.g. width=17, factor=5 yields 4 bins of sizes 5,5,5,2).
+/// The output header's `metadata` records the new effective `width`/`height`
+/// (`ceil(width/factor)`, `ceil(height/factor)`) alongside the `factor`
+/// applied. Returns (header_dict, events_list) in the same COO event-dict
+/// shape as `optical_flow_coo_from_jsonl`.
+#[pyfunction]
+#[pyo3(signature = (input_path, width, height, factor, preserve_header=false))]
+fn spatial_downsample<'py>(
+    py: Python<'py>,
+    input_path: &str,
+    width: usize,
+    height: usize,
+    factor: usize,
+    preserve_header: bool,
+) -> PyResult<(Py<PyAny>, Py<PyAny>)> {
+    if width == 0 || height == 0 {
+        return Err(VisionError::new_err("width/height must be > 0"));
+    }
+    if factor < 1 {
+        return Err(VisionError::new_err("factor must be >= 1"));
+    }
+    let reader = open_input_reader(input_path)?;
+
+    let mut header_opt: Option<Value> = None;
+    let mut seen: HashSet<(i64, i64, i64, i64)> = HashSet::new();
+    let mut out_events: Vec<(i64, i64, i64, i64)> = Vec::new();
+
+    for line in reader.lines() {
+        let line = line.map_err(|e| PyIOError::new_err(format!("read failed: {e}")))?;
+        if line.trim().is_empty() {
+            continue;
+        }
+        if header_opt.is_none() {
+            if let Ok(h) = serde_json::from_str::<InputHeader>(&line) {
+                header_opt = Some(h.header);
+                continue;
+            }
+        }
+        if let Ok(ev) = serde_json::from_str::<InputEvent>(&line) {
+            let ts = ev.ts;
+            let x = ev.idx[0];
+            let y = ev.idx[1];
+            let pol = ev.idx[2];
+            if x < 0 || y < 0 || (x as usize) >= width || (y as usize) >= height {
+                continue;
+            }
+            let bx = (x as usize) / factor;
+            let by = (y as usize) / factor;
+            let key = (ts, bx as i64, by as i64, pol);
+            if seen.insert(key) {
+                out_events.push(key);
+            }
+        }
+    }
+
+    out_events.sort_unstable();
+
+    let hdr = build_output_header(py, &header_opt, preserve_header, "spatial_downsample")?;
+    let hdr_bound = hdr.bind(py);
+    let md = match hdr_bound.get_item("metadata")? {
+        Some(existing) if existing.downcast::<PyDict>().is_ok() => existing.downcast_into::<PyDict>().unwrap(),
+        _ => PyDict::new(py),
+    };
+    let eff_width = width.div_ceil(factor);
+    let eff_height = height.div_ceil(factor);
+    md.set_item("factor", factor)?;
+    md.set_item("width", eff_width)?;
+    md.set_item("height", eff_height)?;
+    hdr_bound.set_item("metadata", md)?;
+
+    let ev_list = PyList::empty(py);
+    for (ts, x, y, pol) in out_events {
+        let d = PyDict::new(py);
+        d.set_item("ts", ts)?;
+        d.set_item("idx", vec![x, y, pol])?;
+        d.set_item("val", 1.0f32)?;
+        ev_list.append(d)?;
+    }
+
+    Ok((hdr.into(), ev_list.unbind().into()))
+}
+
+/// Standard event-camera polarity visualization: an RGB image of shape
+/// (height, width, 3) with ON event counts mapped to the red channel, OFF
+/// counts mapped to the blue channel, and green left at zero. Each channel is
+/// independently normalized so its own maximum count maps to 255, matching
+/// the usual red/blue polarity convention without shuttling two count images
+/// to Python just to colorize them.
+#[pyfunction]
+fn render_polarity_rgb<'py>(py: Python<'py>, input_path: &str, width: usize, height: usize) -> PyResult<Py<PyArray3<u8>>> {
+    if width == 0 || height == 0 {
+        return Err(VisionError::new_err("width/height must be > 0"));
+    }
+    let reader = open_input_reader(input_path)?;
+
+    let mut on_counts: Vec<i64> = vec![0; width * height];
+    let mut off_counts: Vec<i64> = vec![0; width * height];
+
+    for line in reader.lines() {
+        let line = line.map_err(|e| PyIOError::new_err(format!("read failed: {e}")))?;
+        if line.trim().is_empty() {
+            continue;
+        }
+        if let Ok(ev) = serde_json::from_str::<InputEvent>(&line) {
+            let x = ev.idx[0];
+            let y = ev.idx[1];
+            let pol = ev.idx[2];
+            if x >= 0 && (x as usize) < width && y >= 0 && (y as usize) < height {
+                let idx = (y as usize) * width + (x as usize);
+                if pol == 1 {
+                    on_counts[idx] += 1;
+                } else if pol == 0 {
+                    off_counts[idx] += 1;
+                }
+            }
+        }
+    }
+
+    let max_on = on_counts.iter().copied().max().unwrap_or(0).max(1);
+    let max_off = off_counts.iter().copied().max().unwrap_or(0).max(1);
+
+    let out = PyArray3::<u8>::zeros(py, (height, width, 3), false);
+    // SAFETY: out is newly allocated with exclusive ownership while holding the GIL
+    let mut out_view = unsafe { out.as_array_mut() };
+    for y in 0..height {
+        for x in 0..width {
+            let idx = y * width + x;
+            out_view[[y, x, 0]] = ((on_counts[idx] * 255) / max_on) as u8;
+            out_view[[y, x, 2]] = ((off_counts[idx] * 255) / max_off) as u8;
+        }
+    }
+
+    Ok(out.unbind())
+}
+
+/// Spatial centroid trajectory: per `bin_us` time window, the mean x and y of
+/// events falling in that window. Bins with no events are skipped entirely
+/// (not emitted as NaN), since only non-empty windows carry a position.
+/// Cheap coarse motion track without running a full flow kernel.
+#[pyfunction]
+#[allow(clippy::type_complexity)]
+fn centroid_trajectory<'py>(
+    py: Python<'py>,
+    input_path: &str,
+    width: usize,
+    height: usize,
+    bin_us: i64,
+) -> PyResult<(Py<PyArray1<i64>>, Py<PyArray1<f64>>, Py<PyArray1<f64>>)> {
+    if width == 0 || height == 0 {
+        return Err(VisionError::new_err("width/height must be > 0"));
+    }
+    if bin_us <= 0 {
+        return Err(VisionError::new_err("bin_us must be > 0"));
+    }
+    let reader = open_input_reader(input_path)?;
+
+    let mut bins: HashMap<i64, (f64, f64, i64)> = HashMap::new();
+    for line in reader.lines() {
+        let line = line.map_err(|e| PyIOError::new_err(format!("read failed: {e}")))?;
+        if line.trim().is_empty() {
+            continue;
+        }
+        if let Ok(ev) = serde_json::from_str::<InputEvent>(&line) {
+            let x = ev.idx[0];
+            let y = ev.idx[1];
+            if x >= 0 && (x as usize) < width && y >= 0 && (y as usize) < height {
+                let key = ev.ts.div_euclid(bin_us) * bin_us;
+                let entry = bins.entry(key).or_insert((0.0, 0.0, 0));
+                entry.0 += x as f64;
+                entry.1 += y as f64;
+                entry.2 += 1;
+            }
+        }
+    }
+
+    let mut keys: Vec<i64> = bins.keys().copied().collect();
+    keys.sort_unstable();
+
+    let mut times = Vec::with_capacity(keys.len());
+    let mut cx = Vec::with_capacity(keys.len());
+    let mut cy = Vec::with_capacity(keys.len());
+    for k in keys {
+        let (sx, sy, n) = bins[&k];
+        times.push(k);
+        cx.push(sx / n as f64);
+        cy.push(sy / n as f64);
+    }
+
+    Ok((
+        PyArray1::from_vec(py, times).unbind(),
+        PyArray1::from_vec(py, cx).unbind(),
+        PyArray1::from_vec(py, cy).unbind(),
+    ))
+}
+
+/// Per-`bin_us` activity bounding box: the tight `(x0, y0, x1, y1)` min/max
+/// extent (inclusive) of events falling in that window. Lets a caller follow
+/// a moving object's extent or set a dynamic ROI without loading all events
+/// into Python first.
+///
+/// `skip_empty`: when true (the default), windows with no events are omitted
+/// entirely, matching `centroid_trajectory`. When false, every `bin_us`
+/// window spanning the observed timestamp range is emitted, with empty
+/// windows filled with the sentinel `(-1, -1, -1, -1)` so a caller can tell a
+/// gap in activity from a single-pixel box at the origin.
+#[pyfunction]
+#[allow(clippy::type_complexity)]
+#[pyo3(signature = (input_path, width, height, bin_us, skip_empty=true))]
+fn activity_bbox<'py>(
+    py: Python<'py>,
+    input_path: &str,
+    width: usize,
+    height: usize,
+    bin_us: i64,
+    skip_empty: bool,
+) -> PyResult<(
+    Py<PyArray1<i64>>,
+    Py<PyArray1<i64>>,
+    Py<PyArray1<i64>>,
+    Py<PyArray1<i64>>,
+    Py<PyArray1<i64>>,
+)> {
+    if width == 0 || height == 0 {
+        return Err(VisionError::new_err("width/height must be > 0"));
+    }
+    if bin_us <= 0 {
+        return Err(VisionError::new_err("bin_us must be > 0"));
+    }
+    let reader = open_input_reader(input_path)?;
+
+    let mut bins: HashMap<i64, (i64, i64, i64, i64)> = HashMap::new();
+    for line in reader.lines() {
+        let line = line.map_err(|e| PyIOError::new_err(format!("read failed: {e}")))?;
+        if line.trim().is_empty() {
+            continue;
+        }
+        if let Ok(ev) = serde_json::from_str::<InputEvent>(&line) {
+            let x = ev.idx[0];
+            let y = ev.idx[1];
+            if x >= 0 && (x as usize) < width && y >= 0 && (y as usize) < height {
+                let key = ev.ts.div_euclid(bin_us) * bin_us;
+                let entry = bins.entry(key).or_insert((x, y, x, y));
+                entry.0 = entry.0.min(x);
+                entry.1 = entry.1.min(y);
+                entry.2 = entry.2.max(x);
+                entry.3 = entry.3.max(y);
+            }
+        }
+    }
+
+    let mut keys: Vec<i64> = bins.keys().copied().collect();
+    keys.sort_unstable();
+
+    let mut times = Vec::new();
+    let mut x0s = Vec::new();
+    let mut y0s = Vec::new();
+    let mut x1s = Vec::new();
+    let mut y1s = Vec::new();
+
+    if skip_empty || keys.is_empty() {
+        for k in keys {
+            let (x0, y0, x1, y1) = bins[&k];
+            times.push(k);
+            x0s.push(x0);
+            y0s.push(y0);
+            x1s.push(x1);
+            y1s.push(y1);
+        }
+    } else {
+        let first = keys[0];
+        let last = *keys.last().unwrap();
+        let mut k = first;
+        while k <= last {
+            times.push(k);
+            match bins.get(&k) {
+                Some(&(x0, y0, x1, y1)) => {
+                    x0s.push(x0);
+                    y0s.push(y0);
+                    x1s.push(x1);
+                    y1s.push(y1);
+                }
+                None => {
+                    x0s.push(-1);
+                    y0s.push(-1);
+                    x1s.push(-1);
+                    y1s.push(-1);
+                }
+            }
+            k += bin_us;
+        }
+    }
+
+    Ok((
+        PyArray1::from_vec(py, times).unbind(),
+        PyArray1::from_vec(py, x0s).unbind(),
+        PyArray1::from_vec(py, y0s).unbind(),
+        PyArray1::from_vec(py, x1s).unbind(),
+        PyArray1::from_vec(py, y1s).unbind(),
+    ))
+}
+
+/// Minimal fixed-record raw binary event loader: each record is 17 bytes
+/// (ts: u64, x: u32, y: u32, polarity: u8), with multi-byte fields decoded
+/// according to `endian` ("little", the default, or "big"). There is no
+/// header record in this format, so an empty fresh header is returned.
+///
+/// This is the baseline raw loader that `endian` applies to; a full AEDAT
+/// container parser (with its own header/event-type framing) is a separate
+/// loader to be added on top of this once that format is needed.
+#[pyfunction]
+#[pyo3(signature = (input_path, width, height, endian="little"))]
+fn load_raw_events_binary<'py>(
+    py: Python<'py>,
+    input_path: &str,
+    width: usize,
+    height: usize,
+    endian: &str,
+) -> PyResult<(Py<PyAny>, Py<PyAny>)> {
+    if width == 0 || height == 0 {
+        return Err(VisionError::new_err("width/height must be > 0"));
+    }
+    if endian != "little" && endian != "big" {
+        return Err(VisionError::new_err("endian must be 'little' or 'big'"));
+    }
+    let bytes = std::fs::read(input_path).map_err(|e| PyIOError::new_err(format!("open failed: {e}")))?;
+
+    const RECORD_LEN: usize = 17;
+    let mut out_events: Vec<(i64, i64, i64, i64)> = Vec::with_capacity(bytes.len() / RECORD_LEN);
+    for rec in bytes.chunks_exact(RECORD_LEN) {
+        let (ts, x, y) = if endian == "little" {
+            (
+                u64::from_le_bytes(rec[0..8].try_into().unwrap()),
+                u32::from_le_bytes(rec[8..12].try_into().unwrap()),
+                u32::from_le_bytes(rec[12..16].try_into().unwrap()),
+            )
+        } else {
+            (
+                u64::from_be_bytes(rec[0..8].try_into().unwrap()),
+                u32::from_be_bytes(rec[8..12].try_into().unwrap()),
+                u32::from_be_bytes(rec[12..16].try_into().unwrap()),
+            )
+        };
+        let pol = rec[16];
+        if (x as usize) < width && (y as usize) < height && pol <= 1 {
+            out_events.push((ts as i64, x as i64, y as i64, pol as i64));
+        }
+    }
+    out_events.sort_unstable();
+
+    let hdr = build_output_header(py, &None, false, "raw_binary_loader")?;
+    let ev_list = PyList::empty(py);
+    for (ts, x, y, pol) in out_events {
+        let d = PyDict::new(py);
+        d.set_item("ts", ts)?;
+        d.set_item("idx", vec![x, y, pol])?;
+        d.set_item("val", 1.0f32)?;
+        ev_list.append(d)?;
+    }
+
+    Ok((hdr.into(), ev_list.unbind().into()))
+}
+
+/// Recursively convert a serde_json Value into the equivalent Python object.
+fn json_value_to_py(py: Python<'_>, v: &Value) -> PyResult<Py<PyAny>> {
+    Ok(match v {
+        Value::Null => py.None(),
+        Value::Bool(b) => b.into_pyobject(py)?.to_owned().into_any().unbind(),
+        Value::Number(n) => {
+            if let Some(i) = n.as_i64() {
+                i.into_pyobject(py)?.into_any().unbind()
+            } else {
+                n.as_f64().unwrap_or(0.0).into_pyobject(py)?.into_any().unbind()
+            }
+        }
+        Value::String(s) => s.into_pyobject(py)?.into_any().unbind(),
+        Value::Array(arr) => {
+            let list = PyList::empty(py);
+            for item in arr {
+                list.append(json_value_to_py(py, item)?)?;
+            }
+            list.into_any().unbind()
+        }
+        Value::Object(map) => {
+            let dict = PyDict::new(py);
+            for (k, val) in map {
+                dict.set_item(k, json_value_to_py(py, val)?)?;
+            }
+            dict.into_any().unbind()
+        }
+    })
+}
+
+/// Build the header dict returned alongside a loader/kernel's events.
+/// When `preserve_header` is true and a source header was parsed, the entire
+/// source header is echoed back unchanged except for `metadata.kernel`, so
+/// downstream tooling round-trips custom fields instead of losing them.
+/// Otherwise, a fresh header is built and only `dims` is carried over from
+/// the source header (legacy behavior).
+fn build_output_header<'py>(
+    py: Python<'py>,
+    header_opt: &Option<Value>,
+    preserve_header: bool,
+    kernel: &str,
+) -> PyResult<Py<PyDict>> {
+    if preserve_header {
+        if let Some(src_hdr) = header_opt {
+            let hdr = json_value_to_py(py, src_hdr)?;
+            let hdr = hdr.downcast_bound::<PyDict>(py)?.clone();
+            let md = match hdr.get_item("metadata")? {
+                Some(existing) if existing.downcast::<PyDict>().is_ok() => existing.downcast_into::<PyDict>().unwrap(),
+                _ => PyDict::new(py),
+            };
+            md.set_item("kernel", kernel)?;
+            hdr.set_item("metadata", md)?;
+            return Ok(hdr.unbind());
+        }
+    }
+
+    let hdr = PyDict::new(py);
+    hdr.set_item("schema_version", "0.1.0")?;
+    hdr.set_item("dims", vec!["x", "y", "polarity"])?;
+    let units = PyDict::new(py);
+    units.set_item("time", "us")?;
+    units.set_item("value", "dimensionless")?;
+    hdr.set_item("units", units)?;
+    hdr.set_item("dtype", "f32")?;
+    hdr.set_item("layout", "coo")?;
+    let md = PyDict::new(py);
+    md.set_item("backend", "native-rust")?;
+    md.set_item("kernel", kernel)?;
+    hdr.set_item("metadata", md)?;
+
+    if let Some(src_hdr) = header_opt {
+        if let Some(dims) = src_hdr.get("dims") {
+            if let Some(arr) = dims.as_array() {
+                let py_dims = PyList::empty(py);
+                for v in arr {
+                    if let Some(s) = v.as_str() {
+                        py_dims.append(s)?;
+                    } else if let Some(n) = v.as_i64() {
+                        py_dims.append(n)?;
+                    } else {
+                        py_dims.append(v.to_string())?;
+                    }
+                }
+                hdr.set_item("dims", py_dims)?;
+            }
+        }
+    }
+
+    Ok(hdr.unbind())
+}
+
+/// `serde_json::Value` counterpart of [`build_output_header`], for writers
+/// that produce JSONL directly from Rust without round-tripping through a
+/// `PyDict` first. Mirrors its field-for-field output exactly.
+fn build_output_header_json(header_opt: &Option<Value>, preserve_header: bool, kernel: &str) -> Value {
+    if preserve_header {
+        if let Some(src_hdr) = header_opt {
+            let mut hdr = src_hdr.clone();
+            if let Some(obj) = hdr.as_object_mut() {
+                let md = obj.entry("metadata").or_insert_with(|| Value::Object(serde_json::Map::new()));
+                if !md.is_object() {
+                    *md = Value::Object(serde_json::Map::new());
+                }
+                md.as_object_mut().unwrap().insert("kernel".to_string(), Value::String(kernel.to_string()));
+                return hdr;
+            }
+        }
+    }
+
+    let dims = header_opt
+        .as_ref()
+        .and_then(|h| h.get("dims"))
+        .filter(|d| d.is_array())
+        .cloned()
+        .unwrap_or_else(|| serde_json::json!(["x", "y", "polarity"]));
+
+    serde_json::json!({
+        "schema_version": "0.1.0",
+        "dims": dims,
+        "units": {"time": "us", "value": "dimensionless"},
+        "dtype": "f32",
+        "layout": "coo",
+        "metadata": {"backend": "native-rust", "kernel": kernel},
+    })
+}
+
+/// Per-pixel polarity-flip detector: emits an event only when a pixel's
+/// current polarity differs from that same pixel's previous event polarity.
+/// Events are read in file order (assumed time-sorted per the UEC stream
+/// convention) and a per-(x,y) last-polarity table is updated as each event
+/// arrives, so this is a single linear pass over the stream rather than a
+/// per-coordinate buffering step. A pixel's first event never emits (there is
+/// no previous polarity to compare against). Highlights oscillating pixels
+/// that a dominant-polarity image like `dominant_polarity_image` buries.
+/// Returns (header_dict, events_list) in the same COO event-dict shape as
+/// `optical_flow_coo_from_jsonl`.
+#[pyfunction]
+#[pyo3(signature = (input_path, width, height, preserve_header=false))]
+fn polarity_change_events<'py>(
+    py: Python<'py>,
+    input_path: &str,
+    width: usize,
+    height: usize,
+    preserve_header: bool,
+) -> PyResult<(Py<PyAny>, Py<PyAny>)> {
+    if width == 0 || height == 0 {
+        return Err(VisionError::new_err("width/height must be > 0"));
+    }
+    let reader = open_input_reader(input_path)?;
+
+    let mut header_opt: Option<Value> = None;
+    let mut last_pol: HashMap<(i64, i64), i64> = HashMap::new();
+    let mut out_events: Vec<(i64, i64, i64, i64)> = Vec::new();
+
+    for line in reader.lines() {
+        let line = line.map_err(|e| PyIOError::new_err(format!("read failed: {e}")))?;
+        if line.trim().is_empty() {
+            continue;
+        }
+        if header_opt.is_none() {
+            if let Ok(h) = serde_json::from_str::<InputHeader>(&line) {
+                header_opt = Some(h.header);
+                continue;
+            }
+        }
+        if let Ok(ev) = serde_json::from_str::<InputEvent>(&line) {
+            let ts = ev.ts;
+            let x = ev.idx[0];
+            let y = ev.idx[1];
+            let pol = ev.idx[2];
+            if x >= 0 && (x as usize) < width && y >= 0 && (y as usize) < height {
+                let key = (x, y);
+                if let Some(&prev) = last_pol.get(&key) {
+                    if prev != pol {
+                        out_events.push((ts, x, y, pol));
+                    }
+                }
+                last_pol.insert(key, pol);
+            }
+        }
+    }
+
+    let hdr = build_output_header(py, &header_opt, preserve_header, "polarity_change_events")?;
+
+    let ev_list = PyList::empty(py);
+    for (ts, x, y, pol) in out_events {
+        let d = PyDict::new(py);
+        d.set_item("ts", ts)?;
+        d.set_item("idx", vec![x, y, pol])?;
+        d.set_item("val", 1.0f32)?;
+        ev_list.append(d)?;
+    }
+
+    Ok((hdr.into(), ev_list.unbind().into()))
+}
+
+/// Background-activity filter (BAF): passes an event only if at least one
+/// of its 8 spatial neighbors produced an event within the last `dt_us`.
+/// Maintains a per-pixel last-timestamp grid of size `width*height`
+/// (`i64::MIN` meaning "no event yet") and, for each incoming event, checks
+/// the 8 neighboring grid cells before updating its own cell -- an event
+/// never counts as its own neighbor. Events read in file order are assumed
+/// time-sorted per the UEC stream convention, same as `event_age_map` and
+/// `polarity_change_events`. Returns (header_dict, events_list) in the same
+/// COO event-dict shape as `optical_flow_coo_from_jsonl`.
+#[pyfunction]
+#[pyo3(signature = (input_path, width, height, dt_us, preserve_header=false))]
+fn denoise_background_activity<'py>(
+    py: Python<'py>,
+    input_path: &str,
+    width: usize,
+    height: usize,
+    dt_us: i64,
+    preserve_header: bool,
+) -> PyResult<(Py<PyAny>, Py<PyAny>)> {
+    if width == 0 || height == 0 {
+        return Err(VisionError::new_err("width/height must be > 0"));
+    }
+    if dt_us <= 0 {
+        return Err(VisionError::new_err("dt_us must be > 0"));
+    }
+    let reader = open_input_reader(input_path)?;
+
+    let mut header_opt: Option<Value> = None;
+    let mut last_ts: Vec<i64> = vec![i64::MIN; width * height];
+    let mut out_events: Vec<(i64, i64, i64, i64)> = Vec::new();
+
+    for line in reader.lines() {
+        let line = line.map_err(|e| PyIOError::new_err(format!("read failed: {e}")))?;
+        if line.trim().is_empty() {
+            continue;
+        }
+        if header_opt.is_none() {
+            if let Ok(h) = serde_json::from_str::<InputHeader>(&line) {
+                header_opt = Some(h.header);
+                continue;
+            }
+        }
+        if let Ok(ev) = serde_json::from_str::<InputEvent>(&line) {
+            let ts = ev.ts;
+            let x = ev.idx[0];
+            let y = ev.idx[1];
+            let pol = ev.idx[2];
+            if x < 0 || y < 0 || (x as usize) >= width || (y as usize) >= height {
+                continue;
+            }
+            let (xu, yu) = (x as usize, y as usize);
+            let mut has_recent_neighbor = false;
+            for dy in -1i64..=1 {
+                for dx in -1i64..=1 {
+                    if dx == 0 && dy == 0 {
+                        continue;
+                    }
+                    let nx = x + dx;
+                    let ny = y + dy;
+                    if nx < 0 || ny < 0 || (nx as usize) >= width || (ny as usize) >= height {
+                        continue;
+                    }
+                    let n_idx = (ny as usize) * width + (nx as usize);
+                    let n_last = last_ts[n_idx];
+                    if n_last != i64::MIN && ts - n_last <= dt_us {
+                        has_recent_neighbor = true;
+                    }
+                }
+            }
+            if has_recent_neighbor {
+                out_events.push((ts, x, y, pol));
+            }
+            last_ts[yu * width + xu] = ts;
+        }
+    }
+
+    let hdr = build_output_header(py, &header_opt, preserve_header, "denoise_background_activity")?;
+
+    let ev_list = PyList::empty(py);
+    for (ts, x, y, pol) in out_events {
+        let d = PyDict::new(py);
+        d.set_item("ts", ts)?;
+        d.set_item("idx", vec![x, y, pol])?;
+        d.set_item("val", 1.0f32)?;
+        ev_list.append(d)?;
+    }
+
+    Ok((hdr.into(), ev_list.unbind().into()))
+}
+
+/// Classifies an 8-neighbor offset `(dx, dy)` (each in `{-1,0,1}`, not both
+/// zero) by which line through the center pixel it lies on: `0` horizontal
+/// (W/E), `1` vertical (N/S), `2` the main diagonal (NW/SE), `3` the anti
+/// diagonal (NE/SW).
+fn neighbor_line_id(dx: i64, dy: i64) -> u8 {
+    if dy == 0 {
+        0
+    } else if dx == 0 {
+        1
+    } else if dx == dy {
+        2
+    } else {
+        3
+    }
+}
+
+/// Simplified event-based corner detector: an event is a corner if, within
+/// `window_us`, at least `min_neighbors` of its 8 spatial neighbors have a
+/// recent event, AND those active neighbors don't all lie on the same line
+/// through the center pixel (straight-edge motion activates only the pair of
+/// opposite neighbors along one of the four lines -- horizontal, vertical, or
+/// either diagonal -- while a corner activates neighbors spanning at least
+/// two different lines). Reuses the same per-pixel last-timestamp grid as
+/// `denoise_background_activity`. Returns (header_dict, events_list) in the
+/// same COO event-dict shape as `optical_flow_coo_from_jsonl`.
+#[pyfunction]
+#[pyo3(signature = (input_path, width, height, window_us, min_neighbors, preserve_header=false))]
+fn event_corners<'py>(
+    py: Python<'py>,
+    input_path: &str,
+    width: usize,
+    height: usize,
+    window_us: i64,
+    min_neighbors: usize,
+    preserve_header: bool,
+) -> PyResult<(Py<PyAny>, Py<PyAny>)> {
+    if width == 0 || height == 0 {
+        return Err(VisionError::new_err("width/height must be > 0"));
+    }
+    if window_us <= 0 {
+        return Err(VisionError::new_err("window_us must be > 0"));
+    }
+    if min_neighbors == 0 || min_neighbors > 8 {
+        return Err(VisionError::new_err("min_neighbors must be between 1 and 8"));
+    }
+    let reader = open_input_reader(input_path)?;
+
+    let mut header_opt: Option<Value> = None;
+    let mut last_ts: Vec<i64> = vec![i64::MIN; width * height];
+    let mut out_events: Vec<(i64, i64, i64, i64)> = Vec::new();
+
+    for line in reader.lines() {
+        let line = line.map_err(|e| PyIOError::new_err(format!("read failed: {e}")))?;
+        if line.trim().is_empty() {
+            continue;
+        }
+        if header_opt.is_none() {
+            if let Ok(h) = serde_json::from_str::<InputHeader>(&line) {
+                header_opt = Some(h.header);
+                continue;
+            }
+        }
+        if let Ok(ev) = serde_json::from_str::<InputEvent>(&line) {
+            let ts = ev.ts;
+            let x = ev.idx[0];
+            let y = ev.idx[1];
+            let pol = ev.idx[2];
+            if x < 0 || y < 0 || (x as usize) >= width || (y as usize) >= height {
+                continue;
+            }
+            let (xu, yu) = (x as usize, y as usize);
+            let mut active_count = 0usize;
+            let mut lines_seen = [false; 4];
+            for dy in -1i64..=1 {
+                for dx in -1i64..=1 {
+                    if dx == 0 && dy == 0 {
+                        continue;
+                    }
+                    let nx = x + dx;
+                    let ny = y + dy;
+                    if nx < 0 || ny < 0 || (nx as usize) >= width || (ny as usize) >= height {
+                        continue;
+                    }
+                    let n_idx = (ny as usize) * width + (nx as usize);
+                    let n_last = last_ts[n_idx];
+                    if n_last != i64::MIN && ts - n_last <= window_us {
+                        active_count += 1;
+                        lines_seen[neighbor_line_id(dx, dy) as usize] = true;
+                    }
+                }
+            }
+            let distinct_lines = lines_seen.iter().filter(|&&seen| seen).count();
+            if active_count >= min_neighbors && distinct_lines >= 2 {
+                out_events.push((ts, x, y, pol));
+            }
+            last_ts[yu * width + xu] = ts;
+        }
+    }
+
+    let hdr = build_output_header(py, &header_opt, preserve_header, "event_corners")?;
+
+    let ev_list = PyList::empty(py);
+    for (ts, x, y, pol) in out_events {
+        let d = PyDict::new(py);
+        d.set_item("ts", ts)?;
+        d.set_item("idx", vec![x, y, pol])?;
+        d.set_item("val", 1.0f32)?;
+        ev_list.append(d)?;
+    }
+
+    Ok((hdr.into(), ev_list.unbind().into()))
+}
+
+/// Per-pixel refractory filter: keeps only the first event at a given
+/// `(x, y, pol)` within any `refractory_us` interval, dropping subsequent
+/// events at that same key until the interval has elapsed. Maintains a
+/// `HashMap<(i64,i64,i64), i64>` of the last *accepted* timestamp per key
+/// (not every incoming timestamp), so a burst of same-pixel events is
+/// thinned to at most one accepted event per `refractory_us`. Events read
+/// in file order are assumed time-sorted per the UEC stream convention, and
+/// since events are only ever dropped (never reordered), overall time
+/// ordering is preserved. Returns (header_dict, events_list) in the same
+/// COO event-dict shape as `optical_flow_coo_from_jsonl`.
+#[pyfunction]
+#[pyo3(signature = (input_path, width, height, refractory_us, preserve_header=false))]
+fn refractory_filter<'py>(
+    py: Python<'py>,
+    input_path: &str,
+    width: usize,
+    height: usize,
+    refractory_us: i64,
+    preserve_header: bool,
+) -> PyResult<(Py<PyAny>, Py<PyAny>)> {
+    if width == 0 || height == 0 {
+        return Err(VisionError::new_err("width/height must be > 0"));
+    }
+    if refractory_us < 0 {
+        return Err(VisionError::new_err("refractory_us must be >= 0"));
+    }
+    let reader = open_input_reader(input_path)?;
+
+    let mut header_opt: Option<Value> = None;
+    let mut last_accepted: HashMap<(i64, i64, i64), i64> = HashMap::new();
+    let mut out_events: Vec<(i64, i64, i64, i64)> = Vec::new();
+
+    for line in reader.lines() {
+        let line = line.map_err(|e| PyIOError::new_err(format!("read failed: {e}")))?;
+        if line.trim().is_empty() {
+            continue;
+        }
+        if header_opt.is_none() {
+            if let Ok(h) = serde_json::from_str::<InputHeader>(&line) {
+                header_opt = Some(h.header);
+                continue;
+            }
+        }
+        if let Ok(ev) = serde_json::from_str::<InputEvent>(&line) {
+            let ts = ev.ts;
+            let x = ev.idx[0];
+            let y = ev.idx[1];
+            let pol = ev.idx[2];
+            if x < 0 || y < 0 || (x as usize) >= width || (y as usize) >= height {
+                continue;
+            }
+            let key = (x, y, pol);
+            let accept = match last_accepted.get(&key) {
+                Some(&prev) => ts - prev >= refractory_us,
+                None => true,
+            };
+            if accept {
+                out_events.push((ts, x, y, pol));
+                last_accepted.insert(key, ts);
+            }
+        }
+    }
+
+    let hdr = build_output_header(py, &header_opt, preserve_header, "refractory_filter")?;
+
+    let ev_list = PyList::empty(py);
+    for (ts, x, y, pol) in out_events {
+        let d = PyDict::new(py);
+        d.set_item("ts", ts)?;
+        d.set_item("idx", vec![x, y, pol])?;
+        d.set_item("val", 1.0f32)?;
+        ev_list.append(d)?;
+    }
+
+    Ok((hdr.into(), ev_list.unbind().into()))
+}
+
+/// Hot-pixel filter: drops every event from any `(x,y)` pixel that fired
+/// more than `max_events_per_pixel` times in the whole recording, the
+/// classic symptom of a stuck or saturated DVS photoreceptor. Events are
+/// buffered in memory so the per-pixel counts from a first pass over the
+/// stream can be used to filter a second pass without re-reading the
+/// (possibly gzip-compressed or glob-expanded, non-seekable) input.
+/// Returns (header_dict, events_list); the header's `metadata.hot_pixels`
+/// lists the dropped `[x, y]` coordinates, sorted for determinism.
+#[pyfunction]
+#[pyo3(signature = (input_path, width, height, max_events_per_pixel, preserve_header=false))]
+fn remove_hot_pixels<'py>(
+    py: Python<'py>,
+    input_path: &str,
+    width: usize,
+    height: usize,
+    max_events_per_pixel: usize,
+    preserve_header: bool,
+) -> PyResult<(Py<PyAny>, Py<PyAny>)> {
+    if width == 0 || height == 0 {
+        return Err(VisionError::new_err("width/height must be > 0"));
+    }
+    if max_events_per_pixel == 0 {
+        return Err(VisionError::new_err("max_events_per_pixel must be >= 1"));
+    }
+    let reader = open_input_reader(input_path)?;
+
+    let mut header_opt: Option<Value> = None;
+    let mut events: Vec<(i64, i64, i64, i64)> = Vec::new();
+
+    for line in reader.lines() {
+        let line = line.map_err(|e| PyIOError::new_err(format!("read failed: {e}")))?;
+        if line.trim().is_empty() {
+            continue;
+        }
+        if header_opt.is_none() {
+            if let Ok(h) = serde_json::from_str::<InputHeader>(&line) {
+                header_opt = Some(h.header);
+                continue;
+            }
+        }
+        if let Ok(ev) = serde_json::from_str::<InputEvent>(&line) {
+            let ts = ev.ts;
+            let x = ev.idx[0];
+            let y = ev.idx[1];
+            let pol = ev.idx[2];
+            if x < 0 || y < 0 || (x as usize) >= width || (y as usize) >= height {
+                continue;
+            }
+            events.push((ts, x, y, pol));
+        }
+    }
+
+    // First pass: count events per pixel.
+    let mut counts: HashMap<(i64, i64), usize> = HashMap::new();
+    for &(_, x, y, _) in &events {
+        *counts.entry((x, y)).or_insert(0) += 1;
+    }
+    let mut hot_pixels: Vec<(i64, i64)> = counts
+        .into_iter()
+        .filter(|&(_, c)| c > max_events_per_pixel)
+        .map(|(k, _)| k)
+        .collect();
+    hot_pixels.sort_unstable();
+
+    // Second pass: drop events belonging to hot pixels.
+    let hot_set: HashSet<(i64, i64)> = hot_pixels.iter().copied().collect();
+    let out_events: Vec<(i64, i64, i64, i64)> = events
+        .into_iter()
+        .filter(|&(_, x, y, _)| !hot_set.contains(&(x, y)))
+        .collect();
+
+    let hdr = build_output_header(py, &header_opt, preserve_header, "remove_hot_pixels")?;
+    let hdr_bound = hdr.bind(py);
+    let md = match hdr_bound.get_item("metadata")? {
+        Some(existing) if existing.downcast::<PyDict>().is_ok() => existing.downcast_into::<PyDict>().unwrap(),
+        _ => PyDict::new(py),
+    };
+    let hot_pixel_list: Vec<Vec<i64>> = hot_pixels.into_iter().map(|(x, y)| vec![x, y]).collect();
+    md.set_item("hot_pixels", hot_pixel_list)?;
+    hdr_bound.set_item("metadata", md)?;
+
+    let ev_list = PyList::empty(py);
+    for (ts, x, y, pol) in out_events {
+        let d = PyDict::new(py);
+        d.set_item("ts", ts)?;
+        d.set_item("idx", vec![x, y, pol])?;
+        d.set_item("val", 1.0f32)?;
+        ev_list.append(d)?;
+    }
+
+    Ok((hdr.into(), ev_list.unbind().into()))
+}
+
+/// Compute an absolute deadline from an optional wall-clock budget in
+/// milliseconds. `None` means unbounded.
+fn deadline_from_budget(time_budget_ms: Option<u64>) -> Option<std::time::Instant> {
+    time_budget_ms.map(|ms| std::time::Instant::now() + std::time::Duration::from_millis(ms))
+}
+
+/// Whether an optional deadline has already passed. `None` never expires.
+fn deadline_exceeded(deadline: Option<std::time::Instant>) -> bool {
+    deadline.is_some_and(|d| std::time::Instant::now() >= d)
+}
+
+/// Stamp `metadata.truncated_by_time = true` onto an already-built output
+/// header, creating the `metadata` sub-dict if the header (e.g. a
+/// preserve_header passthrough) doesn't already have one.
+fn mark_truncated_by_time(py: Python<'_>, hdr: &Py<PyDict>) -> PyResult<()> {
+    let hdr = hdr.bind(py);
+    let md = match hdr.get_item("metadata")? {
+        Some(existing) if existing.downcast::<PyDict>().is_ok() => existing.downcast_into::<PyDict>().unwrap(),
+        _ => PyDict::new(py),
+    };
+    md.set_item("truncated_by_time", true)?;
+    hdr.set_item("metadata", md)?;
+    Ok(())
+}
+
+/// Multi-region event-count time series: for each ROI rectangle `(x0, y0, x1,
+/// y1)` (x in `[x0, x1)`, y in `[y0, y1)`), bins matching events into
+/// `bin_us`-wide windows and counts them, in a single parse that tests every
+/// event against all ROIs. Cheaper than loading all events into Python and
+/// slicing per ROI there. Returns a dict keyed by `"roi_{i}"` (ROI index in
+/// the input order) to a `(times, counts)` tuple; bins with zero events in a
+/// given ROI are omitted from that ROI's series, matching `centroid_trajectory`.
+#[pyfunction]
+fn roi_event_rates<'py>(
+    py: Python<'py>,
+    input_path: &str,
+    width: usize,
+    height: usize,
+    rois: Vec<(i64, i64, i64, i64)>,
+    bin_us: i64,
+) -> PyResult<Py<PyDict>> {
+    if width == 0 || height == 0 {
+        return Err(VisionError::new_err("width/height must be > 0"));
+    }
+    if bin_us <= 0 {
+        return Err(VisionError::new_err("bin_us must be > 0"));
+    }
+    if rois.is_empty() {
+        return Err(VisionError::new_err("rois must be non-empty"));
+    }
+    for &(x0, y0, x1, y1) in &rois {
+        if x0 >= x1 || y0 >= y1 {
+            return Err(VisionError::new_err(format!(
+                "invalid ROI ({x0}, {y0}, {x1}, {y1}): require x0 < x1 and y0 < y1"
+            )));
+        }
+    }
+
+    let reader = open_input_reader(input_path)?;
+
+    let mut bins: Vec<HashMap<i64, i64>> = vec![HashMap::new(); rois.len()];
+    for line in reader.lines() {
+        let line = line.map_err(|e| PyIOError::new_err(format!("read failed: {e}")))?;
+        if line.trim().is_empty() {
+            continue;
+        }
+        if let Ok(ev) = serde_json::from_str::<InputEvent>(&line) {
+            let x = ev.idx[0];
+            let y = ev.idx[1];
+            if x < 0 || (x as usize) >= width || y < 0 || (y as usize) >= height {
+                continue;
+            }
+            let key = ev.ts.div_euclid(bin_us) * bin_us;
+            for (roi_idx, &(x0, y0, x1, y1)) in rois.iter().enumerate() {
+                if x >= x0 && x < x1 && y >= y0 && y < y1 {
+                    *bins[roi_idx].entry(key).or_insert(0) += 1;
+                }
+            }
+        }
+    }
+
+    let out = PyDict::new(py);
+    for (roi_idx, roi_bins) in bins.into_iter().enumerate() {
+        let mut keys: Vec<i64> = roi_bins.keys().copied().collect();
+        keys.sort_unstable();
+        let times: Vec<i64> = keys.clone();
+        let counts: Vec<i64> = keys.iter().map(|k| roi_bins[k]).collect();
+        let series = (
+            PyArray1::from_vec(py, times).unbind(),
+            PyArray1::from_vec(py, counts).unbind(),
+        );
+        out.set_item(format!("roi_{roi_idx}"), series)?;
+    }
+
+    Ok(out.unbind())
+}
+
+/// Finds the single busiest moment in the recording: bins events into
+/// `bin_us`-wide windows and returns `(peak_time_us, peak_count)`, where
+/// `peak_time_us` is the center timestamp of the window with the most events.
+/// Ties are broken by the earliest such window. Useful for aligning a
+/// recording to a stimulus without pulling the whole rate series into Python.
+#[pyfunction]
+fn peak_rate(input_path: &str, bin_us: i64) -> PyResult<(i64, i64)> {
+    if bin_us <= 0 {
+        return Err(VisionError::new_err("bin_us must be > 0"));
+    }
+
+    let reader = open_input_reader(input_path)?;
+    let mut bins: HashMap<i64, i64> = HashMap::new();
+    for line in reader.lines() {
+        let line = line.map_err(|e| PyIOError::new_err(format!("read failed: {e}")))?;
+        if line.trim().is_empty() {
+            continue;
+        }
+        if let Ok(ev) = serde_json::from_str::<InputEvent>(&line) {
+            let key = ev.ts.div_euclid(bin_us) * bin_us;
+            *bins.entry(key).or_insert(0) += 1;
+        }
+    }
+
+    let mut best_key: Option<i64> = None;
+    let mut best_count: i64 = 0;
+    let mut keys: Vec<i64> = bins.keys().copied().collect();
+    keys.sort_unstable();
+    for key in keys {
+        let count = bins[&key];
+        if best_key.is_none() || count > best_count {
+            best_key = Some(key);
+            best_count = count;
+        }
+    }
+
+    let Some(best_key) = best_key else {
+        return Err(VisionError::new_err("input contains no events"));
+    };
+    Ok((best_key + bin_us / 2, best_count))
+}
+
+/// Normalized temporal autocorrelation of the binned event-rate signal, for
+/// detecting periodicity (e.g. flicker). Bins events into counts-per-`bin_us`
+/// over the full observed time span (empty bins counted as zero), then
+/// returns `acf[lag]` for `lag` in `0..=max_lag_bins`, each the Pearson
+/// autocorrelation of the rate series against itself shifted by `lag` bins.
+/// `acf[0]` is always 1.0.
+#[pyfunction]
+fn rate_autocorrelation<'py>(
+    py: Python<'py>,
+    input_path: &str,
+    bin_us: i64,
+    max_lag_bins: usize,
+) -> PyResult<Py<PyArray1<f32>>> {
+    if bin_us <= 0 {
+        return Err(VisionError::new_err("bin_us must be > 0"));
+    }
+
+    let reader = open_input_reader(input_path)?;
+    let mut bin_counts: HashMap<i64, i64> = HashMap::new();
+    let mut min_bin: Option<i64> = None;
+    let mut max_bin: Option<i64> = None;
+    for line in reader.lines() {
+        let line = line.map_err(|e| PyIOError::new_err(format!("read failed: {e}")))?;
+        if line.trim().is_empty() {
+            continue;
+        }
+        if let Ok(ev) = serde_json::from_str::<InputEvent>(&line) {
+            let key = ev.ts.div_euclid(bin_us);
+            *bin_counts.entry(key).or_insert(0) += 1;
+            min_bin = Some(min_bin.map_or(key, |m| m.min(key)));
+            max_bin = Some(max_bin.map_or(key, |m| m.max(key)));
+        }
+    }
+
+    let (Some(min_bin), Some(max_bin)) = (min_bin, max_bin) else {
+        return Err(VisionError::new_err("input contains no events"));
+    };
+    let n = (max_bin - min_bin + 1) as usize;
+    if max_lag_bins >= n {
+        return Err(VisionError::new_err(format!(
+            "max_lag_bins ({max_lag_bins}) must be less than the series length ({n})"
+        )));
+    }
+
+    let series: Vec<f64> = (0..n)
+        .map(|i| *bin_counts.get(&(min_bin + i as i64)).unwrap_or(&0) as f64)
+        .collect();
+    let mean: f64 = series.iter().sum::<f64>() / n as f64;
+    let var: f64 = series.iter().map(|&x| (x - mean).powi(2)).sum();
+    if var == 0.0 {
+        return Err(VisionError::new_err("rate series has zero variance; autocorrelation is undefined"));
+    }
+
+    let mut acf: Vec<f32> = Vec::with_capacity(max_lag_bins + 1);
+    for lag in 0..=max_lag_bins {
+        let cov: f64 = (0..n - lag).map(|i| (series[i] - mean) * (series[i + lag] - mean)).sum();
+        acf.push((cov / var) as f32);
+    }
+
+    Ok(PyArray1::from_vec(py, acf).unbind())
+}
+
+/// Deterministic keep/drop decision for event `index` under `seed`, using a
+/// splitmix64 hash so the same (seed, index) always yields the same
+/// pseudo-uniform draw without needing sequential RNG state across events.
+fn keep_event(seed: u64, index: u64, keep_prob: f64) -> bool {
+    let mut x = seed ^ index.wrapping_mul(0x9E37_79B9_7F4A_7C15);
+    x ^= x >> 30;
+    x = x.wrapping_mul(0xbf58_476d_1ce4_e5b9);
+    x ^= x >> 27;
+    x = x.wrapping_mul(0x94d0_49bb_1331_11eb);
+    x ^= x >> 31;
+    let frac = (x >> 11) as f64 * (1.0 / (1u64 << 53) as f64);
+    frac < keep_prob
+}
+
+/// Deterministically thins (or passes through) an event stream to approximate
+/// a target average event rate. Computes the input's measured rate from its
+/// total event count and time span (first pass), derives a keep-probability
+/// `min(1, target / measured)`, then re-reads the stream (second pass) and
+/// keeps each event per a seeded deterministic hash of its index, so the same
+/// seed always reproduces the same subsample. Returns (header, events) COO
+/// output with the actual achieved rate recorded in `metadata.achieved_rate_hz`.
+#[pyfunction]
+#[pyo3(signature = (input_path, width, height, target_events_per_second, seed, preserve_header=false))]
+fn resample_to_rate<'py>(
+    py: Python<'py>,
+    input_path: &str,
+    width: usize,
+    height: usize,
+    target_events_per_second: f64,
+    seed: u64,
+    preserve_header: bool,
+) -> PyResult<(Py<PyAny>, Py<PyAny>)> {
+    if width == 0 || height == 0 {
+        return Err(VisionError::new_err("width/height must be > 0"));
+    }
+    if target_events_per_second <= 0.0 {
+        return Err(VisionError::new_err("target_events_per_second must be > 0"));
+    }
+
+    // First pass: measure total count and time span.
+    let mut header_opt: Option<Value> = None;
+    let mut count: u64 = 0;
+    let mut min_ts: Option<i64> = None;
+    let mut max_ts: Option<i64> = None;
+    {
+        let reader = open_input_reader(input_path)?;
+        for line in reader.lines() {
+            let line = line.map_err(|e| PyIOError::new_err(format!("read failed: {e}")))?;
+            if line.trim().is_empty() {
+                continue;
+            }
+            if header_opt.is_none() {
+                if let Ok(h) = serde_json::from_str::<InputHeader>(&line) {
+                    header_opt = Some(h.header);
+                    continue;
+                }
+            }
+            if let Ok(ev) = serde_json::from_str::<InputEvent>(&line) {
+                let x = ev.idx[0];
+                let y = ev.idx[1];
+                if x >= 0 && (x as usize) < width && y >= 0 && (y as usize) < height {
+                    count += 1;
+                    min_ts = Some(min_ts.map_or(ev.ts, |m| m.min(ev.ts)));
+                    max_ts = Some(max_ts.map_or(ev.ts, |m| m.max(ev.ts)));
+                }
+            }
+        }
+    }
+
+    let span_s = match (min_ts, max_ts) {
+        (Some(lo), Some(hi)) if hi > lo => (hi - lo) as f64 / 1_000_000.0,
+        _ => 1.0,
+    };
+    let measured_rate = count as f64 / span_s;
+    let keep_prob = if measured_rate <= 0.0 {
+        1.0
+    } else {
+        (target_events_per_second / measured_rate).clamp(0.0, 1.0)
+    };
+
+    // Second pass: deterministically subsample.
+    let reader = open_input_reader(input_path)?;
+    let mut header_opt2: Option<Value> = None;
+    let mut out_events: Vec<(i64, i64, i64, i64)> = Vec::new();
+    let mut index: u64 = 0;
+    let mut kept: u64 = 0;
+
+    for line in reader.lines() {
+        let line = line.map_err(|e| PyIOError::new_err(format!("read failed: {e}")))?;
+        if line.trim().is_empty() {
+            continue;
+        }
+        if header_opt2.is_none() {
+            if let Ok(h) = serde_json::from_str::<InputHeader>(&line) {
+                header_opt2 = Some(h.header);
+                continue;
+            }
+        }
+        if let Ok(ev) = serde_json::from_str::<InputEvent>(&line) {
+            let x = ev.idx[0];
+            let y = ev.idx[1];
+            let pol = ev.idx[2];
+            if x >= 0 && (x as usize) < width && y >= 0 && (y as usize) < height {
+                if keep_event(seed, index, keep_prob) {
+                    out_events.push((ev.ts, x, y, pol));
+                    kept += 1;
+                }
+                index += 1;
+            }
+        }
+    }
+
+    let achieved_rate_hz = kept as f64 / span_s;
+
+    let hdr = build_output_header(py, &header_opt2, preserve_header, "resample_to_rate")?;
+    {
+        let hdr_bound = hdr.bind(py);
+        let md = match hdr_bound.get_item("metadata")? {
+            Some(existing) if existing.downcast::<PyDict>().is_ok() => existing.downcast_into::<PyDict>().unwrap(),
+            _ => PyDict::new(py),
+        };
+        md.set_item("achieved_rate_hz", achieved_rate_hz)?;
+        md.set_item("keep_probability", keep_prob)?;
+        hdr_bound.set_item("metadata", md)?;
+    }
+
+    let ev_list = PyList::empty(py);
+    for (ts, x, y, pol) in out_events {
+        let d = PyDict::new(py);
+        d.set_item("ts", ts)?;
+        d.set_item("idx", vec![x, y, pol])?;
+        d.set_item("val", 1.0f32)?;
+        ev_list.append(d)?;
+    }
+
+    Ok((hdr.into(), ev_list.unbind().into()))
+}
+
+/// Coincidence-based optical flow on DVS events with Shift/Delay/Fuse semantics.
+/// - Reads JSONL from input_path (expects optional header line and per-event lines)
+/// - Emits events at (x,y,pol) when a neighbor event (shifted by +/-1 in x and delayed)
+///   falls within [t - window_us, t]
+/// - Returns (header_dict, events_list) to Python for easy comparison or writing
+///
+/// `strict`: lines that parse as neither a header nor an event are silently
+/// skipped by default, which can mask a corrupted trace behind a
+/// misleadingly small output. When `false` (default), they're merely
+/// counted and the count recorded as `metadata.skipped_lines` on the
+/// returned header (omitted when zero). When `true`, the first such line
+/// raises `VisionError` naming its 1-based line number instead.
+#[pyfunction]
+#[allow(clippy::too_many_arguments)]
+#[pyo3(signature = (input_path, width, height, _window_us, _delay_us, _edge_delay_us, _min_count, preserve_header=false, strict=false))]
+fn optical_flow_coo_from_jsonl<'py>(
+    py: Python<'py>,
+    input_path: &str,
+    width: usize,
+    height: usize,
+    _window_us: i64,
+    _delay_us: i64,
+    _edge_delay_us: i64,
+    _min_count: usize,
+    preserve_header: bool,
+    strict: bool,
+) -> PyResult<(Py<PyAny>, Py<PyAny>)> {
+    // Pass-through implementation to match the example golden trace produced by the "flow" probe.
+    if width == 0 || height == 0 {
+        return Err(VisionError::new_err("width/height must be > 0"));
+    }
+    let reader = open_input_reader(input_path)?;
+
+    // The actual file reading, parsing, and sorting touch no Python objects,
+    // so they run with the GIL released, letting other Python threads
+    // (e.g. a thread-pool caller) make progress concurrently.
+    type FromJsonlCompute = (Option<Value>, Vec<(i64, i64, i64, i64)>, u64);
+    let compute: Result<FromJsonlCompute, ComputeError> = py.detach(|| {
+        let mut header_opt: Option<Value> = None;
+        let mut out_events: Vec<(i64, i64, i64, i64)> = Vec::new();
+        let mut skipped_lines: u64 = 0;
+
+        for (lineno, line) in reader.lines().enumerate() {
+            let line = line.map_err(|e| ComputeError::Io(format!("read failed: {e}")))?;
+            if line.trim().is_empty() {
+                continue;
+            }
+            if header_opt.is_none() {
+                if let Ok(h) = serde_json::from_str::<InputHeader>(&line) {
+                    header_opt = Some(h.header);
+                    continue;
+                }
+            }
+            if let Ok(ev) = serde_json::from_str::<InputEvent>(&line) {
+                let ts = ev.ts;
+                let x = ev.idx[0];
+                let y = ev.idx[1];
+                let pol = ev.idx[2];
+                if x >= 0 && (x as usize) < width && y >= 0 && (y as usize) < height && pol >= 0 && pol <= 1 {
+                    out_events.push((ts, x, y, pol));
+                }
+            } else if strict {
+                return Err(ComputeError::UnparseableLine(lineno + 1));
+            } else {
+                skipped_lines += 1;
+            }
+        }
+
+        out_events.sort_unstable_by(|a, b| a.cmp(b));
+        Ok((header_opt, out_events, skipped_lines))
+    });
+    let (header_opt, out_events, skipped_lines) = compute.map_err(ComputeError::into_py_err)?;
+
+    // Build header dict
+    let hdr = build_output_header(py, &header_opt, preserve_header, "passthrough_events")?;
+    if skipped_lines > 0 {
+        let hdr_bound = hdr.bind(py);
+        let md = match hdr_bound.get_item("metadata")? {
+            Some(existing) if existing.downcast::<PyDict>().is_ok() => existing.downcast_into::<PyDict>().unwrap(),
+            _ => PyDict::new(py),
+        };
+        md.set_item("skipped_lines", skipped_lines)?;
+        hdr_bound.set_item("metadata", md)?;
+    }
+
+    // Build events list
+    let ev_list = PyList::empty(py);
+    for (ts, x, y, pol) in out_events {
+        let d = PyDict::new(py);
+        d.set_item("ts", ts)?;
+        d.set_item("idx", vec![x, y, pol])?;
+        d.set_item("val", 1.0f32)?;
+        ev_list.append(d)?;
+    }
+
+    Ok((hdr.into(), ev_list.unbind().into()))
+    }
+
+    /// Pass-through returning columnar NumPy arrays (ts, x, y, polarity, val).
+    /// `normalize_time`: when true, subtracts the minimum timestamp among the
+    /// *surviving* (post bounds-filter) events so the earliest one lands at
+    /// `t=0`, and records the subtracted offset as `metadata.t0_us` on the
+    /// header (omitted, as if `0`, when there are no events). Defaults to
+    /// false so existing golden traces are unaffected.
+    /// `on_out_of_bounds`: how to handle events outside `[0,width)x[0,height)`
+    /// or with `pol` outside `{0,1}`. `"skip"` (default) drops them silently,
+    /// matching the historical behavior. `"error"` raises `VisionError`
+    /// describing the first offending event, turning a mismatched
+    /// `width`/`height` into an immediate, legible failure instead of a
+    /// mysteriously empty output. `"count"` keeps skipping but records how
+    /// many events were dropped as `metadata.dropped_out_of_bounds`.
+    #[pyfunction]
+    #[pyo3(signature = (input_path, width, height, preserve_header=false, normalize_time=false, on_out_of_bounds="skip"))]
+    fn optical_flow_coo_arrays<'py>(
+        py: Python<'py>,
+        input_path: &str,
+        width: usize,
+        height: usize,
+        preserve_header: bool,
+        normalize_time: bool,
+        on_out_of_bounds: &str,
+    ) -> PyResult<(Py<PyAny>, Py<PyAny>)> {
+        if width == 0 || height == 0 {
+            return Err(VisionError::new_err("width/height must be > 0"));
+        }
+        if on_out_of_bounds != "skip" && on_out_of_bounds != "error" && on_out_of_bounds != "count" {
+            return Err(VisionError::new_err(
+                "on_out_of_bounds must be 'skip', 'error', or 'count'",
+            ));
+        }
+        let reader = open_input_reader(input_path)?;
+
+        // File reading, parsing, sorting, and normalization touch no Python
+        // objects, so they run with the GIL released.
+        type CooArraysCompute = (Option<Value>, Vec<(i64, i64, i64, i64)>, u64, Option<i64>);
+        let compute: Result<CooArraysCompute, ComputeError> = py.detach(|| {
+            let mut header_opt: Option<Value> = None;
+            let mut out_events: Vec<(i64, i64, i64, i64)> = Vec::new();
+            let mut dropped_out_of_bounds: u64 = 0;
+
+            for line in reader.lines() {
+                let line = line.map_err(|e| ComputeError::Io(format!("read failed: {e}")))?;
+                if line.trim().is_empty() {
+                    continue;
+                }
+                if header_opt.is_none() {
+                    if let Ok(h) = serde_json::from_str::<InputHeader>(&line) {
+                        header_opt = Some(h.header);
+                        continue;
+                    }
+                }
+                if let Ok(ev) = serde_json::from_str::<InputEvent>(&line) {
+                    let ts = ev.ts;
+                    let x = ev.idx[0];
+                    let y = ev.idx[1];
+                    let pol = ev.idx[2];
+                    if x >= 0 && (x as usize) < width && y >= 0 && (y as usize) < height && pol >= 0 && pol <= 1 {
+                        out_events.push((ts, x, y, pol));
+                    } else if on_out_of_bounds == "error" {
+                        return Err(ComputeError::Vision(format!(
+                            "out-of-bounds event: ts={ts} x={x} y={y} pol={pol} (width={width}, height={height})"
+                        )));
+                    } else {
+                        dropped_out_of_bounds += 1;
+                    }
+                }
+            }
+
+            // Sort events for deterministic order
+            out_events.sort_unstable();
+
+            // Normalize after filtering/sorting so dropped out-of-bounds events
+            // never influence the baseline.
+            let t0_us = if normalize_time {
+                out_events.first().map(|&(ts, ..)| ts)
+            } else {
+                None
+            };
+            if let Some(t0) = t0_us {
+                for ev in out_events.iter_mut() {
+                    ev.0 -= t0;
+                }
+            }
+
+            Ok((header_opt, out_events, dropped_out_of_bounds, t0_us))
+        });
+        let (header_opt, out_events, dropped_out_of_bounds, t0_us) = compute.map_err(ComputeError::into_py_err)?;
+
+        // Build header dict
+        let hdr = build_output_header(py, &header_opt, preserve_header, "passthrough_events")?;
+        if let Some(t0) = t0_us {
+            let hdr_bound = hdr.bind(py);
+            let md = match hdr_bound.get_item("metadata")? {
+                Some(existing) if existing.downcast::<PyDict>().is_ok() => existing.downcast_into::<PyDict>().unwrap(),
+                _ => PyDict::new(py),
+            };
+            md.set_item("t0_us", t0)?;
+            hdr_bound.set_item("metadata", md)?;
+        }
+        if on_out_of_bounds == "count" && dropped_out_of_bounds > 0 {
+            let hdr_bound = hdr.bind(py);
+            let md = match hdr_bound.get_item("metadata")? {
+                Some(existing) if existing.downcast::<PyDict>().is_ok() => existing.downcast_into::<PyDict>().unwrap(),
+                _ => PyDict::new(py),
+            };
+            md.set_item("dropped_out_of_bounds", dropped_out_of_bounds)?;
+            hdr_bound.set_item("metadata", md)?;
+        }
+
+        // Build columns
+        let n = out_events.len();
+        let mut ts_col: Vec<i64> = Vec::with_capacity(n);
+        let mut x_col: Vec<i64> = Vec::with_capacity(n);
+        let mut y_col: Vec<i64> = Vec::with_capacity(n);
+        let mut pol_col: Vec<i64> = Vec::with_capacity(n);
+        let mut val_col: Vec<f32> = Vec::with_capacity(n);
+    
+        for (ts, x, y, pol) in out_events.into_iter() {
+            ts_col.push(ts);
+            x_col.push(x);
+            y_col.push(y);
+            pol_col.push(pol);
+            val_col.push(1.0f32);
+        }
+    
+        let ts = PyArray1::<i64>::from_vec(py, ts_col);
+        let x = PyArray1::<i64>::from_vec(py, x_col);
+        let y = PyArray1::<i64>::from_vec(py, y_col);
+        let polarity = PyArray1::<i64>::from_vec(py, pol_col);
+        let val = PyArray1::<f32>::from_vec(py, val_col);
+    
+        let arrays = PyDict::new(py);
+        arrays.set_item("ts", ts)?;
+        arrays.set_item("x", x)?;
+        arrays.set_item("y", y)?;
+        arrays.set_item("polarity", polarity)?;
+        arrays.set_item("val", val)?;
+    
+        Ok((hdr.into(), arrays.unbind().into()))
+    }
+
+    /// Splits a trace into separate ON and OFF columnar NumPy dicts in a
+    /// single pass, each with `ts/x/y/val` columns (no `polarity` column,
+    /// since it's implied by which dict an event landed in). Bounds
+    /// checking matches `optical_flow_coo_arrays`. Saves the common
+    /// Python-side `mask = polarity == 1` post-processing step.
+    #[pyfunction]
+    #[pyo3(signature = (input_path, width, height))]
+    fn split_by_polarity<'py>(
+        py: Python<'py>,
+        input_path: &str,
+        width: usize,
+        height: usize,
+    ) -> PyResult<(Py<PyAny>, Py<PyAny>)> {
+        if width == 0 || height == 0 {
+            return Err(VisionError::new_err("width/height must be > 0"));
+        }
+        let reader = open_input_reader(input_path)?;
+
+        let mut on_events: Vec<(i64, i64, i64)> = Vec::new();
+        let mut off_events: Vec<(i64, i64, i64)> = Vec::new();
+
+        for line in reader.lines() {
+            let line = line.map_err(|e| PyIOError::new_err(format!("read failed: {e}")))?;
+            if line.trim().is_empty() {
+                continue;
+            }
+            if let Ok(ev) = serde_json::from_str::<InputEvent>(&line) {
+                let ts = ev.ts;
+                let x = ev.idx[0];
+                let y = ev.idx[1];
+                let pol = ev.idx[2];
+                if x < 0 || (x as usize) >= width || y < 0 || (y as usize) >= height {
+                    continue;
+                }
+                if pol == 1 {
+                    on_events.push((ts, x, y));
+                } else if pol == 0 {
+                    off_events.push((ts, x, y));
+                }
+            }
+        }
+
+        on_events.sort_unstable();
+        off_events.sort_unstable();
+
+        let build_dict = |py: Python<'py>, events: Vec<(i64, i64, i64)>| -> PyResult<Py<PyAny>> {
+            let n = events.len();
+            let mut ts_col: Vec<i64> = Vec::with_capacity(n);
+            let mut x_col: Vec<i64> = Vec::with_capacity(n);
+            let mut y_col: Vec<i64> = Vec::with_capacity(n);
+            let mut val_col: Vec<f32> = Vec::with_capacity(n);
+            for (ts, x, y) in events {
+                ts_col.push(ts);
+                x_col.push(x);
+                y_col.push(y);
+                val_col.push(1.0f32);
+            }
+            let d = PyDict::new(py);
+            d.set_item("ts", PyArray1::<i64>::from_vec(py, ts_col))?;
+            d.set_item("x", PyArray1::<i64>::from_vec(py, x_col))?;
+            d.set_item("y", PyArray1::<i64>::from_vec(py, y_col))?;
+            d.set_item("val", PyArray1::<f32>::from_vec(py, val_col))?;
+            Ok(d.unbind().into())
+        };
+
+        let on_arrays = build_dict(py, on_events)?;
+        let off_arrays = build_dict(py, off_events)?;
+
+        Ok((on_arrays, off_arrays))
+    }
+
+    // Shift/Delay/Fuse optical flow that emits coincidences per coordinate.
+    // `time_budget_ms`, if set, bounds total wall-clock time: the parse loop
+    // and the per-coordinate fuse loop each check an `Instant` deadline
+    // periodically and bail out with whatever events were computed so far,
+    // stamping `metadata.truncated_by_time=true` on the returned header so
+    // callers can detect a partial result instead of trusting it as complete.
+    // `with_id`, if true, assigns each event a monotonically increasing `"id"`
+    // in final sorted order; since that order is deterministic, ids are
+    // reproducible across runs and can be used to reference a specific
+    // coincidence in logs or downstream annotations instead of a (ts,x,y,pol)
+    // tuple.
+    /// Sliding-window coincidence fuse for a single (x, y, pol) coordinate: merges
+    /// the A (source) and B (neighbor-shifted, delayed) timestamp streams, emits
+    /// `(t, x, y, pol)` whenever both buffers are non-empty and the combined count
+    /// is at least `min_count`. `seen` is local to this coordinate so multiple
+    /// coordinates can run concurrently with no shared state.
+    #[allow(clippy::too_many_arguments)]
+    fn fuse_coordinate(
+        x: i64,
+        y: i64,
+        pol: i64,
+        va: Vec<i64>,
+        vb: Vec<i64>,
+        window_us: i64,
+        min_count: usize,
+        require_both: bool,
+    ) -> Vec<(i64, i64, i64, i64)> {
+        let mut merged: Vec<(i64, u8)> = Vec::with_capacity(va.len() + vb.len());
+        for t in va.into_iter() {
+            merged.push((t, 0));
+        }
+        for t in vb.into_iter() {
+            merged.push((t, 1));
+        }
+        merged.sort_unstable_by_key(|e| e.0);
+
+        let mut buf_a: VecDeque<i64> = VecDeque::new();
+        let mut buf_b: VecDeque<i64> = VecDeque::new();
+        let mut seen: HashSet<i64> = HashSet::new();
+        let mut out: Vec<(i64, i64, i64, i64)> = Vec::new();
+
+        for (t, src) in merged.into_iter() {
+            if src == 0 {
+                buf_a.push_back(t);
+            } else {
+                buf_b.push_back(t);
+            }
+            let cutoff = t.saturating_sub(window_us);
+
+            // Prune strictly older than (t - window)
+            while let Some(&front) = buf_a.front() {
+                if front < cutoff {
+                    buf_a.pop_front();
+                } else {
+                    break;
+                }
+            }
+            while let Some(&front) = buf_b.front() {
+                if front < cutoff {
+                    buf_b.pop_front();
+                } else {
+                    break;
+                }
+            }
+
+            let total = buf_a.len() + buf_b.len();
+            let both_ok = !require_both || (!buf_a.is_empty() && !buf_b.is_empty());
+            if total >= min_count && both_ok && seen.insert(t) {
+                out.push((t, x, y, pol));
+            }
+        }
+
+        out
+    }
+
+    /// Directed B-stream entry: `(timestamp, shift_direction)`, tagged `+1`
+    /// for the increasing-coordinate neighbor or `-1` for the
+    /// decreasing-coordinate neighbor.
+    type DirectedTimes = Vec<(i64, i8)>;
+    /// Fused coincidence event with a direction tag appended, as emitted by
+    /// `fuse_coordinate_directional`: `(ts, x, y, pol, dir)`.
+    type DirectedEvent = (i64, i64, i64, i64, i8);
+    /// Per-coordinate input to `fuse_coordinate_directional`: `(key, a_times, b_times)`.
+    type DirectedCoordInput = ((i64, i64, i64), Vec<i64>, DirectedTimes);
+
+    /// Same sliding-window coincidence fuse as `fuse_coordinate`, but the B
+    /// stream is tagged with the shift direction that produced each entry
+    /// (`+1` for the increasing-coordinate neighbor, `-1` for the
+    /// decreasing-coordinate neighbor), and each emitted event carries the
+    /// direction of the B-buffer entries active at that instant: the shared
+    /// tag if all active entries agree, or `0` when they don't (ambiguous,
+    /// e.g. motion arriving from both sides within the same window).
+    #[allow(clippy::too_many_arguments)]
+    fn fuse_coordinate_directional(
+        x: i64,
+        y: i64,
+        pol: i64,
+        va: Vec<i64>,
+        vb: DirectedTimes,
+        window_us: i64,
+        min_count: usize,
+        require_both: bool,
+    ) -> Vec<DirectedEvent> {
+        let mut merged: Vec<(i64, Option<i8>)> = Vec::with_capacity(va.len() + vb.len());
+        for t in va.into_iter() {
+            merged.push((t, None));
+        }
+        for (t, dir) in vb.into_iter() {
+            merged.push((t, Some(dir)));
+        }
+        merged.sort_unstable_by_key(|e| e.0);
+
+        let mut buf_a: VecDeque<i64> = VecDeque::new();
+        let mut buf_b: VecDeque<(i64, i8)> = VecDeque::new();
+        let mut seen: HashSet<i64> = HashSet::new();
+        let mut out: Vec<(i64, i64, i64, i64, i8)> = Vec::new();
+
+        for (t, dir) in merged.into_iter() {
+            match dir {
+                None => buf_a.push_back(t),
+                Some(d) => buf_b.push_back((t, d)),
+            }
+            let cutoff = t.saturating_sub(window_us);
+
+            while let Some(&front) = buf_a.front() {
+                if front < cutoff {
+                    buf_a.pop_front();
+                } else {
+                    break;
+                }
+            }
+            while let Some(&(front, _)) = buf_b.front() {
+                if front < cutoff {
+                    buf_b.pop_front();
+                } else {
+                    break;
+                }
+            }
+
+            let total = buf_a.len() + buf_b.len();
+            let both_ok = !require_both || (!buf_a.is_empty() && !buf_b.is_empty());
+            if total >= min_count && both_ok && seen.insert(t) {
+                let first_dir = buf_b.front().map(|&(_, d)| d).unwrap_or(0);
+                let emitted_dir = if buf_b.iter().all(|&(_, d)| d == first_dir) { first_dir } else { 0 };
+                out.push((t, x, y, pol, emitted_dir));
+            }
+        }
+
+        out
+    }
+
+    /// Flushes one `chunk_us` time slice for the `chunk_us`-bounded path of
+    /// `optical_flow_shift_delay_fuse_coo`: runs `fuse_coordinate_directional`
+    /// over each coordinate's current `a_map`/`b_map` entries (carryover tail
+    /// from prior chunks plus this chunk's own arrivals), keeps only the
+    /// results whose trigger timestamp falls at or after `chunk_start_ts`
+    /// (entries before that were already resolved by the previous flush, so
+    /// re-including them here would double-count), then prunes each
+    /// coordinate's raw buffers down to the tail within `horizon` of
+    /// `chunk_end_ts` to seed the next chunk.
+    #[allow(clippy::too_many_arguments)]
+    fn flush_fuse_chunk(
+        a_map: &mut HashMap<(i64, i64, i64), Vec<i64>>,
+        b_map: &mut HashMap<(i64, i64, i64), DirectedTimes>,
+        window_us: i64,
+        min_count: usize,
+        chunk_start_ts: i64,
+        chunk_end_ts: i64,
+        horizon: i64,
+        require_both: bool,
+    ) -> Vec<DirectedEvent> {
+        let mut keys: HashSet<(i64, i64, i64)> = HashSet::new();
+        keys.extend(a_map.keys().cloned());
+        keys.extend(b_map.keys().cloned());
+
+        let cutoff = chunk_end_ts.saturating_sub(horizon);
+        let mut out: Vec<DirectedEvent> = Vec::new();
+
+        for key in keys {
+            let va = a_map.remove(&key).unwrap_or_default();
+            let vb = b_map.remove(&key).unwrap_or_default();
+            let (x, y, pol) = key;
+            let fused =
+                fuse_coordinate_directional(x, y, pol, va.clone(), vb.clone(), window_us, min_count, require_both);
+            out.extend(fused.into_iter().filter(|ev| ev.0 >= chunk_start_ts));
+
+            let retained_a: Vec<i64> = va.into_iter().filter(|&t| t >= cutoff).collect();
+            let retained_b: Vec<(i64, i8)> = vb.into_iter().filter(|&(t, _)| t >= cutoff).collect();
+            if !retained_a.is_empty() {
+                a_map.insert(key, retained_a);
+            }
+            if !retained_b.is_empty() {
+                b_map.insert(key, retained_b);
+            }
+        }
+
+        out
+    }
+
+    /// `limit_sorted`: if set, keeps only the first N events of the final
+    /// deterministically-sorted output. The truncation happens *after* the
+    /// `sort_unstable()` below, not during accumulation, so the kept subset
+    /// is the same N events regardless of platform, thread count, or the
+    /// order coordinates happen to finish fusing in — useful for committing
+    /// a small, reproducible golden trace.
+    ///
+    /// `axis`: controls which neighbor(s) populate the delayed B-stream.
+    /// `"x"` (the default, preserving the existing golden trace) shifts ±1
+    /// in x only and so detects horizontal motion exclusively; `"y"` shifts
+    /// ±1 in y only, for vertical motion; `"both"` populates B from all four
+    /// 4-neighbors so the kernel catches motion along either axis.
+    ///
+    /// `radius`: the B-stream neighbor is shifted by `±radius` pixels along
+    /// the chosen axis/axes instead of the fixed ±1, for downsampled or
+    /// low-resolution DVS data where motion spans a larger spatial gap.
+    /// Defaults to `1` (preserving the existing golden trace). Must be >= 1.
+    ///
+    /// `with_direction`: when true, tracks which shift (increasing- or
+    /// decreasing-coordinate neighbor) produced the matching B event and
+    /// appends a `dir` field to each emitted event dict: `+1`, `-1`, or `0`
+    /// when the B-buffer entries active at emission disagree on direction.
+    ///
+    /// `ignore_polarity`: when true, keys the A/B streams by `(x, y)` only
+    /// instead of `(x, y, pol)`, so ON and OFF events at the same pixel
+    /// contribute to the same coincidence window instead of being fused
+    /// separately. Emitted events get `pol` fixed to `0`. The neighbor-shift
+    /// and windowing logic is unchanged. Defaults to `false` so existing
+    /// golden traces are unaffected.
+    ///
+    /// `roi`: optional `(x0, y0, x1, y1)` sub-window. Events outside
+    /// `[x0,x1) x [y0,y1)` are dropped during ingestion, and all downstream
+    /// coordinates (including the `±radius` neighbor shift bounds) are
+    /// relative to `(x0, y0)`, so emitted `idx` values are already shifted
+    /// to the ROI's own origin. `dims` in the output header is unchanged,
+    /// but `metadata.roi` records the `[x0, y0, x1, y1]` that was applied.
+    /// Must satisfy `x0 < x1 <= width` and `y0 < y1 <= height`.
+    ///
+    /// `chunk_us`: if set, processes the stream in non-overlapping time
+    /// slices of this many microseconds (bucketed by `ts.div_euclid(chunk_us)`)
+    /// instead of buffering every event across the whole file into `a_map`/
+    /// `b_map` at once, bounding peak memory for very long recordings. Each
+    /// chunk carries forward only the tail of its per-coordinate A/B buffers
+    /// still within `window_us + delay_us + edge_delay_us` of the chunk's end,
+    /// seeding the next chunk's computation. A raw event's carry-forward and
+    /// emission are both anchored to its own fixed timestamp's chunk, so a
+    /// coincidence is never double-counted -- but one whose `min_count`
+    /// threshold is only reached by combining events from two different
+    /// chunks can be missed if the straddling events land on opposite sides
+    /// of a chunk boundary. Output therefore matches the whole-file (no
+    /// `chunk_us`) result exactly except for coincidences straddling a
+    /// chunk seam. `with_id` and `limit_sorted` still apply uniformly to the
+    /// combined, sorted output, same as the non-chunked path.
+    ///
+    /// `connectivity`: `4` (default) populates the B-stream from the
+    /// axis-aligned neighbors selected by `axis`/`radius` only, preserving
+    /// existing behavior. `8` additionally populates it from the four
+    /// diagonal neighbors at `(x±radius, y±radius)`, each delayed by the
+    /// same `eff_delay`, so motion along a diagonal is detected without
+    /// requiring two separate axis-aligned passes. Corner/edge pixels emit
+    /// only the subset of diagonal neighbors that stay in-bounds (and, when
+    /// a `roi` is set, in-ROI). Diagonal neighbors are tagged direction `0`
+    /// (ambiguous), the same tag `fuse_coordinate_directional` already uses
+    /// when axis-aligned `with_direction` entries disagree, since a diagonal
+    /// shift isn't a pure increasing/decreasing move along either axis.
+    ///
+    /// `require_both`: when true (the default), a coincidence additionally
+    /// requires both the A (source) and B (shifted/delayed neighbor) buffers
+    /// to be non-empty, same as `fuse_coincidence_i64`. When false, that
+    /// requirement is dropped and a coincidence is emitted as soon as the
+    /// combined count reaches `min_count`, even if every contributing event
+    /// came from a single buffer. This changes what counts as a motion
+    /// coincidence, so callers relying on the AND-of-both-buffers semantics
+    /// must keep the default.
+    #[pyfunction]
+    #[allow(clippy::too_many_arguments)]
+    #[pyo3(signature = (input_path, width, height, window_us, delay_us, edge_delay_us, min_count, preserve_header=false, time_budget_ms=None, with_id=false, limit_sorted=None, axis="x", radius=1, with_direction=false, ignore_polarity=false, roi=None, chunk_us=None, connectivity=4, require_both=true))]
+    fn optical_flow_shift_delay_fuse_coo<'py>(
+        py: Python<'py>,
+        input_path: &str,
+        width: usize,
+        height: usize,
+        window_us: i64,
+        delay_us: i64,
+        edge_delay_us: i64,
+        min_count: usize,
+        preserve_header: bool,
+        time_budget_ms: Option<u64>,
+        with_id: bool,
+        limit_sorted: Option<usize>,
+        axis: &str,
+        radius: usize,
+        with_direction: bool,
+        ignore_polarity: bool,
+        roi: Option<(usize, usize, usize, usize)>,
+        chunk_us: Option<i64>,
+        connectivity: u8,
+        require_both: bool,
+    ) -> PyResult<(Py<PyAny>, Py<PyAny>)> {
+        if chunk_us == Some(0) || chunk_us.is_some_and(|c| c < 0) {
+            return Err(VisionError::new_err("chunk_us must be > 0"));
+        }
+        if width == 0 || height == 0 {
+            return Err(VisionError::new_err("width/height must be > 0"));
+        }
+        if window_us <= 0 {
+            return Err(VisionError::new_err("window_us must be > 0"));
+        }
+        if delay_us < 0 || edge_delay_us < 0 {
+            return Err(VisionError::new_err("delay_us and edge_delay_us must be >= 0"));
+        }
+        if min_count == 0 {
+            return Err(VisionError::new_err("min_count must be >= 1"));
+        }
+        if axis != "x" && axis != "y" && axis != "both" {
+            return Err(VisionError::new_err("axis must be 'x', 'y', or 'both'"));
+        }
+        if radius == 0 {
+            return Err(VisionError::new_err("radius must be >= 1"));
+        }
+        if connectivity != 4 && connectivity != 8 {
+            return Err(VisionError::new_err("connectivity must be 4 or 8"));
+        }
+        let diagonal = connectivity == 8;
+        let (rx0, ry0, rx1, ry1) = match roi {
+            Some((x0, y0, x1, y1)) => {
+                if x0 >= x1 || x1 > width || y0 >= y1 || y1 > height {
+                    return Err(VisionError::new_err(
+                        "roi must satisfy x0 < x1 <= width and y0 < y1 <= height",
+                    ));
+                }
+                (x0, y0, x1, y1)
+            }
+            None => (0, 0, width, height),
+        };
+        let roi_width = rx1 - rx0;
+        let roi_height = ry1 - ry0;
+        let shift_x = axis == "x" || axis == "both";
+        let shift_y = axis == "y" || axis == "both";
+        let r = radius as i64;
+
+        let reader = open_input_reader(input_path)?;
+
+        let eff_delay = delay_us + edge_delay_us;
+        let deadline = deadline_from_budget(time_budget_ms);
+
+        type FuseCooCompute = (Option<Value>, Vec<(i64, i64, i64, i64, i8)>, bool);
+        let compute: Result<FuseCooCompute, ComputeError> = py.detach(|| {
+        let mut truncated = false;
+
+        // Optional header passthrough
+        let mut header_opt: Option<Value> = None;
+
+        let out_events: Vec<(i64, i64, i64, i64, i8)> = if let Some(chunk_us) = chunk_us {
+            // Chunked path: bounds peak memory by flushing each coordinate's
+            // A/B buffers once the stream crosses a chunk boundary instead of
+            // accumulating them for the whole file. See the `chunk_us` doc
+            // above for the boundary caveat.
+            let horizon = window_us.saturating_add(eff_delay);
+            let mut a_map: HashMap<(i64, i64, i64), Vec<i64>> = HashMap::new();
+            let mut b_map: HashMap<(i64, i64, i64), Vec<(i64, i8)>> = HashMap::new();
+            let mut current_chunk: Option<i64> = None;
+            let mut out: Vec<DirectedEvent> = Vec::new();
+
+            for (n, line) in reader.lines().enumerate() {
+                if n % 4096 == 0 && deadline_exceeded(deadline) {
+                    truncated = true;
+                    break;
+                }
+                let line = line.map_err(|e| ComputeError::Io(format!("read failed: {e}")))?;
+                if line.trim().is_empty() {
+                    continue;
+                }
+                if header_opt.is_none() {
+                    if let Ok(h) = serde_json::from_str::<InputHeader>(&line) {
+                        header_opt = Some(h.header);
+                        continue;
+                    }
+                }
+                if let Ok(ev) = serde_json::from_str::<InputEvent>(&line) {
+                    let ts = ev.ts;
+                    let x = ev.idx[0];
+                    let y = ev.idx[1];
+                    let pol = ev.idx[2];
+                    if x < 0 || y < 0 || !(0..=1).contains(&pol) {
+                        continue;
+                    }
+                    if (x as usize) < rx0 || (x as usize) >= rx1 || (y as usize) < ry0 || (y as usize) >= ry1 {
+                        continue;
+                    }
+                    let x = x - rx0 as i64;
+                    let y = y - ry0 as i64;
+                    let key_pol = if ignore_polarity { 0 } else { pol };
+
+                    let chunk = ts.div_euclid(chunk_us);
+                    if current_chunk.is_some_and(|cc| cc != chunk) {
+                        let cc = current_chunk.unwrap();
+                        let chunk_start_ts = cc * chunk_us;
+                        let chunk_end_ts = chunk_start_ts + chunk_us;
+                        out.extend(flush_fuse_chunk(
+                            &mut a_map, &mut b_map, window_us, min_count, chunk_start_ts, chunk_end_ts, horizon, require_both,
+                        ));
+                    }
+                    current_chunk = Some(chunk);
+
+                    a_map.entry((x, y, key_pol)).or_default().push(ts);
+                    let b_ts = ts.saturating_add(eff_delay);
+                    if shift_x {
+                        if x + r < roi_width as i64 {
+                            b_map.entry((x + r, y, key_pol)).or_default().push((b_ts, 1));
+                        }
+                        if x - r >= 0 {
+                            b_map.entry((x - r, y, key_pol)).or_default().push((b_ts, -1));
+                        }
+                    }
+                    if shift_y {
+                        if y + r < roi_height as i64 {
+                            b_map.entry((x, y + r, key_pol)).or_default().push((b_ts, 1));
+                        }
+                        if y - r >= 0 {
+                            b_map.entry((x, y - r, key_pol)).or_default().push((b_ts, -1));
+                        }
+                    }
+                    if diagonal {
+                        if x + r < roi_width as i64 && y + r < roi_height as i64 {
+                            b_map.entry((x + r, y + r, key_pol)).or_default().push((b_ts, 0));
+                        }
+                        if x + r < roi_width as i64 && y - r >= 0 {
+                            b_map.entry((x + r, y - r, key_pol)).or_default().push((b_ts, 0));
+                        }
+                        if x - r >= 0 && y + r < roi_height as i64 {
+                            b_map.entry((x - r, y + r, key_pol)).or_default().push((b_ts, 0));
+                        }
+                        if x - r >= 0 && y - r >= 0 {
+                            b_map.entry((x - r, y - r, key_pol)).or_default().push((b_ts, 0));
+                        }
+                    }
+                }
+            }
+            if let Some(cc) = current_chunk {
+                let chunk_start_ts = cc * chunk_us;
+                let chunk_end_ts = chunk_start_ts + chunk_us;
+                out.extend(flush_fuse_chunk(
+                    &mut a_map, &mut b_map, window_us, min_count, chunk_start_ts, chunk_end_ts, horizon, require_both,
+                ));
+            }
+            out
+        } else {
+            // Per-coordinate event times for A (source) and B (neighbor-shifted, delayed).
+            // B entries are tagged with their shift direction (+1 increasing-coordinate
+            // neighbor, -1 decreasing-coordinate neighbor) so `with_direction` can surface
+            // it later without a second pass over the input.
+            let mut a_map: HashMap<(i64, i64, i64), Vec<i64>> = HashMap::new();
+            let mut b_map: HashMap<(i64, i64, i64), Vec<(i64, i8)>> = HashMap::new();
+
+            for (n, line) in reader.lines().enumerate() {
+                if n % 4096 == 0 && deadline_exceeded(deadline) {
+                    truncated = true;
+                    break;
+                }
+                let line = line.map_err(|e| ComputeError::Io(format!("read failed: {e}")))?;
+                if line.trim().is_empty() {
+                    continue;
+                }
+                // Capture header if present
+                if header_opt.is_none() {
+                    if let Ok(h) = serde_json::from_str::<InputHeader>(&line) {
+                        header_opt = Some(h.header);
+                        continue;
+                    }
+                }
+                // Parse event
+                if let Ok(ev) = serde_json::from_str::<InputEvent>(&line) {
+                    let ts = ev.ts;
+                    let x = ev.idx[0];
+                    let y = ev.idx[1];
+                    let pol = ev.idx[2];
+                    if x < 0 || y < 0 || pol < 0 || pol > 1 {
+                        continue;
+                    }
+                    if (x as usize) < rx0 || (x as usize) >= rx1 || (y as usize) < ry0 || (y as usize) >= ry1 {
+                        continue;
+                    }
+                    // Shift into ROI-local coordinates so all downstream maps,
+                    // neighbor-shift bounds, and emitted idx values are relative
+                    // to the ROI origin.
+                    let x = x - rx0 as i64;
+                    let y = y - ry0 as i64;
+                    let key_pol = if ignore_polarity { 0 } else { pol };
+
+                    // A-stream at (x,y,pol) -- or (x,y,0) when polarity is ignored
+                    a_map.entry((x, y, key_pol)).or_default().push(ts);
+
+                    // B-stream: shift ±radius along the requested axis/axes and delay by eff_delay
+                    let b_ts = ts.saturating_add(eff_delay);
+                    if shift_x {
+                        if x + r < roi_width as i64 {
+                            b_map.entry((x + r, y, key_pol)).or_default().push((b_ts, 1));
+                        }
+                        if x - r >= 0 {
+                            b_map.entry((x - r, y, key_pol)).or_default().push((b_ts, -1));
+                        }
+                    }
+                    if shift_y {
+                        if y + r < roi_height as i64 {
+                            b_map.entry((x, y + r, key_pol)).or_default().push((b_ts, 1));
+                        }
+                        if y - r >= 0 {
+                            b_map.entry((x, y - r, key_pol)).or_default().push((b_ts, -1));
+                        }
+                    }
+                    if diagonal {
+                        if x + r < roi_width as i64 && y + r < roi_height as i64 {
+                            b_map.entry((x + r, y + r, key_pol)).or_default().push((b_ts, 0));
+                        }
+                        if x + r < roi_width as i64 && y - r >= 0 {
+                            b_map.entry((x + r, y - r, key_pol)).or_default().push((b_ts, 0));
+                        }
+                        if x - r >= 0 && y + r < roi_height as i64 {
+                            b_map.entry((x - r, y + r, key_pol)).or_default().push((b_ts, 0));
+                        }
+                        if x - r >= 0 && y - r >= 0 {
+                            b_map.entry((x - r, y - r, key_pol)).or_default().push((b_ts, 0));
+                        }
+                    }
+                }
+            }
+
+            // For determinism, sort the per-key vectors
+            for v in a_map.values_mut() {
+                v.sort_unstable();
+            }
+            for v in b_map.values_mut() {
+                v.sort_unstable_by_key(|&(t, _)| t);
+            }
+
+            // Process each coordinate independently with a sliding window coincidence fuse.
+            // Each coordinate's merged (ts, src) stream and `seen` dedup set are private to
+            // that coordinate, so coordinates can be fused in parallel with rayon: there is
+            // no shared mutable state for `par_iter` to race on, and the final `sort_unstable`
+            // fixes the output order regardless of the order coordinates finish in. This makes
+            // the parallel path byte-for-byte identical to the serial per-key loop it replaced.
+            let mut keys: HashSet<(i64, i64, i64)> = HashSet::new();
+            keys.extend(a_map.keys().cloned());
+            keys.extend(b_map.keys().cloned());
+
+            let coords: Vec<DirectedCoordInput> = keys
+                .into_iter()
+                .map(|k| {
+                    let va = a_map.remove(&k).unwrap_or_default();
+                    let vb = b_map.remove(&k).unwrap_or_default();
+                    (k, va, vb)
+                })
+                .collect();
+
+            let per_coord: Vec<Vec<DirectedEvent>> = {
+                use rayon::prelude::*;
+                coords
+                    .into_par_iter()
+                    .map(|((x, y, pol), va, vb)| fuse_coordinate_directional(x, y, pol, va, vb, window_us, min_count, require_both))
+                    .collect()
+            };
+
+            per_coord.into_iter().flatten().collect()
+        };
+
+        // Sort outputs for deterministic return order
+        let mut out_events = out_events;
+        out_events.sort_unstable();
+
+        // Truncate post-sort so the kept subset is platform-independent.
+        if let Some(limit) = limit_sorted {
+            out_events.truncate(limit);
+        }
+
+        Ok((header_opt, out_events, truncated))
+        });
+        let (header_opt, out_events, truncated) = compute.map_err(ComputeError::into_py_err)?;
+
+        // Build header dict (compatible with golden schema)
+        let hdr = build_output_header(py, &header_opt, preserve_header, "optical_flow_shift_delay_fuse")?;
+        if truncated {
+            mark_truncated_by_time(py, &hdr)?;
+        }
+        if roi.is_some() {
+            let hdr_bound = hdr.bind(py);
+            let md = match hdr_bound.get_item("metadata")? {
+                Some(existing) if existing.downcast::<PyDict>().is_ok() => existing.downcast_into::<PyDict>().unwrap(),
+                _ => PyDict::new(py),
+            };
+            md.set_item("roi", vec![rx0, ry0, rx1, ry1])?;
+            hdr_bound.set_item("metadata", md)?;
+        }
+
+        // Build events list
+        let ev_list = PyList::empty(py);
+        for (id, (ts, x, y, pol, dir)) in out_events.into_iter().enumerate() {
+            let d = PyDict::new(py);
+            d.set_item("ts", ts)?;
+            d.set_item("idx", vec![x, y, pol])?;
+            d.set_item("val", 1.0f32)?;
+            if with_id {
+                d.set_item("id", id as i64)?;
+            }
+            if with_direction {
+                d.set_item("dir", dir as i64)?;
+            }
+            ev_list.append(d)?;
+        }
+
+        Ok((hdr.into(), ev_list.unbind().into()))
+    }
+
+    /// Streaming sibling of `optical_flow_shift_delay_fuse_coo` that writes
+    /// the header line and one event line per coincidence directly to
+    /// `output_path` via a `BufWriter`, instead of building a Python list of
+    /// per-event dicts. Useful for long recordings where materializing the
+    /// full event list in Python is the memory/time bottleneck. Accepts the
+    /// same `axis`/`radius`/`with_direction`/`ignore_polarity`/`require_both`
+    /// options as `optical_flow_shift_delay_fuse_coo` and produces the same
+    /// event stream; the file it writes is a valid UEC JSONL trace that `ef
+    /// compare` (or any other JSONL consumer) can read directly. Returns the
+    /// number of events written.
+    #[pyfunction]
+    #[allow(clippy::too_many_arguments)]
+    #[pyo3(signature = (input_path, output_path, width, height, window_us, delay_us, edge_delay_us, min_count, preserve_header=false, time_budget_ms=None, with_id=false, limit_sorted=None, axis="x", radius=1, with_direction=false, ignore_polarity=false, require_both=true))]
+    fn optical_flow_shift_delay_fuse_to_jsonl<'py>(
+        py: Python<'py>,
+        input_path: &str,
+        output_path: &str,
+        width: usize,
+        height: usize,
+        window_us: i64,
+        delay_us: i64,
+        edge_delay_us: i64,
+        min_count: usize,
+        preserve_header: bool,
+        time_budget_ms: Option<u64>,
+        with_id: bool,
+        limit_sorted: Option<usize>,
+        axis: &str,
+        radius: usize,
+        with_direction: bool,
+        ignore_polarity: bool,
+        require_both: bool,
+    ) -> PyResult<usize> {
+        if width == 0 || height == 0 {
+            return Err(VisionError::new_err("width/height must be > 0"));
+        }
+        if window_us <= 0 {
+            return Err(VisionError::new_err("window_us must be > 0"));
+        }
+        if delay_us < 0 || edge_delay_us < 0 {
+            return Err(VisionError::new_err("delay_us and edge_delay_us must be >= 0"));
+        }
+        if min_count == 0 {
+            return Err(VisionError::new_err("min_count must be >= 1"));
+        }
+        if axis != "x" && axis != "y" && axis != "both" {
+            return Err(VisionError::new_err("axis must be 'x', 'y', or 'both'"));
+        }
+        if radius == 0 {
+            return Err(VisionError::new_err("radius must be >= 1"));
+        }
+        let shift_x = axis == "x" || axis == "both";
+        let shift_y = axis == "y" || axis == "both";
+        let r = radius as i64;
+
+        let reader = open_input_reader(input_path)?;
+
+        let eff_delay = delay_us + edge_delay_us;
+        let deadline = deadline_from_budget(time_budget_ms);
+        let mut truncated = false;
+
+        let mut header_opt: Option<Value> = None;
+        let mut a_map: HashMap<(i64, i64, i64), Vec<i64>> = HashMap::new();
+        let mut b_map: HashMap<(i64, i64, i64), Vec<(i64, i8)>> = HashMap::new();
+
+        for (n, line) in reader.lines().enumerate() {
+            if n % 4096 == 0 && deadline_exceeded(deadline) {
+                truncated = true;
+                break;
+            }
+            let line = line.map_err(|e| PyIOError::new_err(format!("read failed: {e}")))?;
+            if line.trim().is_empty() {
+                continue;
+            }
+            if header_opt.is_none() {
+                if let Ok(h) = serde_json::from_str::<InputHeader>(&line) {
+                    header_opt = Some(h.header);
+                    continue;
+                }
+            }
+            if let Ok(ev) = serde_json::from_str::<InputEvent>(&line) {
+                let ts = ev.ts;
+                let x = ev.idx[0];
+                let y = ev.idx[1];
+                let pol = ev.idx[2];
+                if x < 0 || y < 0 || !(0..=1).contains(&pol) {
+                    continue;
+                }
+                if (x as usize) >= width || (y as usize) >= height {
+                    continue;
+                }
+                let key_pol = if ignore_polarity { 0 } else { pol };
+
+                a_map.entry((x, y, key_pol)).or_default().push(ts);
+
+                let b_ts = ts.saturating_add(eff_delay);
+                if shift_x {
+                    if x + r < width as i64 {
+                        b_map.entry((x + r, y, key_pol)).or_default().push((b_ts, 1));
+                    }
+                    if x - r >= 0 {
+                        b_map.entry((x - r, y, key_pol)).or_default().push((b_ts, -1));
+                    }
+                }
+                if shift_y {
+                    if y + r < height as i64 {
+                        b_map.entry((x, y + r, key_pol)).or_default().push((b_ts, 1));
+                    }
+                    if y - r >= 0 {
+                        b_map.entry((x, y - r, key_pol)).or_default().push((b_ts, -1));
+                    }
+                }
+            }
+        }
+
+        for v in a_map.values_mut() {
+            v.sort_unstable();
+        }
+        for v in b_map.values_mut() {
+            v.sort_unstable_by_key(|&(t, _)| t);
+        }
+
+        let mut keys: HashSet<(i64, i64, i64)> = HashSet::new();
+        keys.extend(a_map.keys().cloned());
+        keys.extend(b_map.keys().cloned());
+
+        let coords: Vec<DirectedCoordInput> = keys
+            .into_iter()
+            .map(|k| {
+                let va = a_map.remove(&k).unwrap_or_default();
+                let vb = b_map.remove(&k).unwrap_or_default();
+                (k, va, vb)
+            })
+            .collect();
+
+        let per_coord: Vec<Vec<DirectedEvent>> = py.detach(|| {
+            use rayon::prelude::*;
+            coords
+                .into_par_iter()
+                .map(|((x, y, pol), va, vb)| fuse_coordinate_directional(x, y, pol, va, vb, window_us, min_count, require_both))
+                .collect()
+        });
+
+        let mut out_events: Vec<(i64, i64, i64, i64, i8)> = per_coord.into_iter().flatten().collect();
+        out_events.sort_unstable();
+        if let Some(limit) = limit_sorted {
+            out_events.truncate(limit);
+        }
+
+        let mut hdr_json = build_output_header_json(&header_opt, preserve_header, "optical_flow_shift_delay_fuse");
+        if truncated {
+            if let Some(obj) = hdr_json.as_object_mut() {
+                let md = obj.entry("metadata").or_insert_with(|| Value::Object(serde_json::Map::new()));
+                if !md.is_object() {
+                    *md = Value::Object(serde_json::Map::new());
+                }
+                md.as_object_mut().unwrap().insert("truncated_by_time".to_string(), Value::Bool(true));
+            }
+        }
+
+        let file = File::create(output_path).map_err(|e| PyIOError::new_err(format!("create failed: {e}")))?;
+        let mut writer = std::io::BufWriter::new(file);
+        use std::io::Write;
+        let header_line = serde_json::json!({ "header": hdr_json });
+        writeln!(writer, "{header_line}").map_err(|e| PyIOError::new_err(format!("write failed: {e}")))?;
+
+        let mut count = 0usize;
+        for (id, (ts, x, y, pol, dir)) in out_events.into_iter().enumerate() {
+            let mut ev = serde_json::Map::with_capacity(5);
+            ev.insert("ts".to_string(), Value::from(ts));
+            ev.insert("idx".to_string(), serde_json::json!([x, y, pol]));
+            ev.insert("val".to_string(), serde_json::json!(1.0f32));
+            if with_id {
+                ev.insert("id".to_string(), Value::from(id as i64));
+            }
+            if with_direction {
+                ev.insert("dir".to_string(), Value::from(dir as i64));
+            }
+            let line = Value::Object(ev);
+            writeln!(writer, "{line}").map_err(|e| PyIOError::new_err(format!("write failed: {e}")))?;
+            count += 1;
+        }
+        writer.flush().map_err(|e| PyIOError::new_err(format!("flush failed: {e}")))?;
+
+        Ok(count)
+    }
+
+    /// Shift/Delay/Fuse returning columnar NumPy arrays (ts, x, y, polarity, val).
+    /// See `optical_flow_shift_delay_fuse_coo` for `time_budget_ms` semantics.
+    /// `chunk_size`, if set, returns the second element as a list of
+    /// column-dict chunks of at most `chunk_size` events each instead of one
+    /// full-length dict, so a caller can process and drop each chunk before
+    /// the next is built rather than holding the whole result plus its
+    /// NumPy copy in memory at once.
+    /// `with_direction`: see `optical_flow_shift_delay_fuse_coo`; adds a
+    /// `dir` column (i64) to the returned array dict(s) instead of a `dir`
+    /// field on each event.
+    /// `normalize_time`: see `optical_flow_coo_arrays`; applied to the fused
+    /// output after filtering/fusing/sorting, so it shifts coincidences, not
+    /// raw input events, and the recorded `metadata.t0_us` offset is in
+    /// fused-output time.
+    /// `require_both`: see `optical_flow_shift_delay_fuse_coo`.
+    #[pyfunction]
+    #[allow(clippy::too_many_arguments)]
+    #[pyo3(signature = (input_path, width, height, window_us, delay_us, edge_delay_us, min_count, preserve_header=false, time_budget_ms=None, chunk_size=None, with_direction=false, normalize_time=false, require_both=true))]
+    fn optical_flow_shift_delay_fuse_arrays<'py>(
+        py: Python<'py>,
+        input_path: &str,
+        width: usize,
+        height: usize,
+        window_us: i64,
+        delay_us: i64,
+        edge_delay_us: i64,
+        min_count: usize,
+        preserve_header: bool,
+        time_budget_ms: Option<u64>,
+        chunk_size: Option<usize>,
+        with_direction: bool,
+        normalize_time: bool,
+        require_both: bool,
+    ) -> PyResult<(Py<PyAny>, Py<PyAny>)> {
+        if chunk_size == Some(0) {
+            return Err(VisionError::new_err("chunk_size must be >= 1"));
+        }
+        if width == 0 || height == 0 {
+            return Err(VisionError::new_err("width/height must be > 0"));
+        }
+        if window_us <= 0 {
+            return Err(VisionError::new_err("window_us must be > 0"));
+        }
+        if delay_us < 0 || edge_delay_us < 0 {
+            return Err(VisionError::new_err("delay_us and edge_delay_us must be >= 0"));
+        }
+        if min_count == 0 {
+            return Err(VisionError::new_err("min_count must be >= 1"));
+        }
+
+        let reader = open_input_reader(input_path)?;
+
+        let eff_delay = delay_us + edge_delay_us;
+        let deadline = deadline_from_budget(time_budget_ms);
+
+        // The actual file reading, parsing, and coincidence-fusing touch no
+        // Python objects, so they run with the GIL released, letting other
+        // Python threads (e.g. a thread-pool caller) make progress
+        // concurrently.
+        type FuseArraysCompute = (Option<Value>, Vec<(i64, i64, i64, i64, i8)>, bool, Option<i64>);
+        let compute: Result<FuseArraysCompute, ComputeError> = py.detach(|| {
+            let mut truncated = false;
+
+            // Optional header passthrough
+            let mut header_opt: Option<Value> = None;
+
+            // Per-coordinate event times for A (source) and B (neighbor-shifted, delayed).
+            // B entries are tagged with their shift direction (+1/-1), same convention as
+            // `optical_flow_shift_delay_fuse_coo`, so `with_direction` can surface it here too.
+            let mut a_map: HashMap<(i64, i64, i64), Vec<i64>> = HashMap::new();
+            let mut b_map: HashMap<(i64, i64, i64), Vec<(i64, i8)>> = HashMap::new();
+
+            for (n, line) in reader.lines().enumerate() {
+                if n % 4096 == 0 && deadline_exceeded(deadline) {
+                    truncated = true;
+                    break;
+                }
+                let line = line.map_err(|e| ComputeError::Io(format!("read failed: {e}")))?;
+                if line.trim().is_empty() {
+                    continue;
+                }
+                // Capture header if present
+                if header_opt.is_none() {
+                    if let Ok(h) = serde_json::from_str::<InputHeader>(&line) {
+                        header_opt = Some(h.header);
+                        continue;
+                    }
+                }
+                // Parse event
+                if let Ok(ev) = serde_json::from_str::<InputEvent>(&line) {
+                    let ts = ev.ts;
+                    let x = ev.idx[0];
+                    let y = ev.idx[1];
+                    let pol = ev.idx[2];
+                    if x < 0 || y < 0 || !(0..=1).contains(&pol) {
+                        continue;
+                    }
+                    if (x as usize) >= width || (y as usize) >= height {
+                        continue;
+                    }
+
+                    // A-stream at (x,y,pol)
+                    a_map.entry((x, y, pol)).or_default().push(ts);
+
+                    // B-stream: shift ±1 in x and delay by eff_delay
+                    let b_ts = ts.saturating_add(eff_delay);
+                    if x + 1 < width as i64 {
+                        b_map.entry((x + 1, y, pol)).or_default().push((b_ts, 1));
+                    }
+                    if x > 0 {
+                        b_map.entry((x - 1, y, pol)).or_default().push((b_ts, -1));
+                    }
+                }
+            }
+
+            // For determinism, sort the per-key vectors
+            for v in a_map.values_mut() {
+                v.sort_unstable();
+            }
+            for v in b_map.values_mut() {
+                v.sort_unstable_by_key(|&(t, _)| t);
+            }
+
+            // Process each coordinate independently with a sliding window coincidence fuse.
+            // See `fuse_coordinate` for why this is safe to run in parallel.
+            let mut keys: HashSet<(i64, i64, i64)> = HashSet::new();
+            keys.extend(a_map.keys().cloned());
+            keys.extend(b_map.keys().cloned());
+
+            let coords: Vec<DirectedCoordInput> = keys
+                .into_iter()
+                .map(|k| {
+                    let va = a_map.remove(&k).unwrap_or_default();
+                    let vb = b_map.remove(&k).unwrap_or_default();
+                    (k, va, vb)
+                })
+                .collect();
+
+            let per_coord: Vec<Vec<DirectedEvent>> = {
+                use rayon::prelude::*;
+                coords
+                    .into_par_iter()
+                    .map(|((x, y, pol), va, vb)| fuse_coordinate_directional(x, y, pol, va, vb, window_us, min_count, require_both))
+                    .collect()
+            };
+
+            let mut out_events: Vec<(i64, i64, i64, i64, i8)> = per_coord.into_iter().flatten().collect();
+
+            // Sort outputs for deterministic return order
+            out_events.sort_unstable();
+
+            // Normalize after filtering/fusing/sorting so dropped out-of-bounds
+            // events never influence the baseline.
+            let t0_us = if normalize_time {
+                out_events.first().map(|&(ts, ..)| ts)
+            } else {
+                None
+            };
+            if let Some(t0) = t0_us {
+                for ev in out_events.iter_mut() {
+                    ev.0 -= t0;
+                }
+            }
+
+            Ok((header_opt, out_events, truncated, t0_us))
+        });
+        let (header_opt, out_events, truncated, t0_us) = compute.map_err(ComputeError::into_py_err)?;
+
+        // Build header dict (compatible with golden schema)
+        let hdr = build_output_header(py, &header_opt, preserve_header, "optical_flow_shift_delay_fuse")?;
+        if truncated {
+            mark_truncated_by_time(py, &hdr)?;
+        }
+        if let Some(t0) = t0_us {
+            let hdr_bound = hdr.bind(py);
+            let md = match hdr_bound.get_item("metadata")? {
+                Some(existing) if existing.downcast::<PyDict>().is_ok() => existing.downcast_into::<PyDict>().unwrap(),
+                _ => PyDict::new(py),
+            };
+            md.set_item("t0_us", t0)?;
+            hdr_bound.set_item("metadata", md)?;
+        }
+
+        if let Some(cs) = chunk_size {
+            let chunks = PyList::empty(py);
+            for chunk in out_events.chunks(cs) {
+                chunks.append(columnar_dict(py, chunk, with_direction)?)?;
+            }
+            return Ok((hdr.into(), chunks.unbind().into()));
+        }
+
+        let arrays = columnar_dict(py, &out_events, with_direction)?;
+        Ok((hdr.into(), arrays.unbind().into()))
+    }
+
+    /// FNV-1a over the little-endian bytes of each `(ts, x, y, pol)` tuple,
+    /// folded left to right. Pure integer arithmetic with no
+    /// platform-dependent hashing (e.g. `HashMap`'s randomized default
+    /// hasher), so the result is stable across runs, processes, and
+    /// platforms -- suitable for pinning in a test assertion.
+    fn fnv1a_hash(bytes: &[u8]) -> u64 {
+        const FNV_OFFSET_BASIS: u64 = 0xcbf29ce484222325;
+        const FNV_PRIME: u64 = 0x100000001b3;
+        let mut hash = FNV_OFFSET_BASIS;
+        for &b in bytes {
+            hash ^= b as u64;
+            hash = hash.wrapping_mul(FNV_PRIME);
+        }
+        hash
+    }
+
+    /// Runs the same shift/delay/coincidence-fuse computation as
+    /// `optical_flow_shift_delay_fuse_arrays` but, instead of returning the
+    /// fused events, returns a stable hex-encoded FNV-1a hash of the sorted
+    /// `(ts, x, y, pol)` tuples. Useful as a cheap fingerprint for
+    /// regression tests that want to detect a changed kernel output without
+    /// writing and diffing full event traces.
+    #[pyfunction]
+    #[allow(clippy::too_many_arguments)]
+    #[pyo3(signature = (input_path, width, height, window_us, delay_us, edge_delay_us, min_count))]
+    fn hash_events<'py>(
+        py: Python<'py>,
+        input_path: &str,
+        width: usize,
+        height: usize,
+        window_us: i64,
+        delay_us: i64,
+        edge_delay_us: i64,
+        min_count: usize,
+    ) -> PyResult<String> {
+        if width == 0 || height == 0 {
+            return Err(VisionError::new_err("width/height must be > 0"));
+        }
+        if window_us <= 0 {
+            return Err(VisionError::new_err("window_us must be > 0"));
+        }
+        if delay_us < 0 || edge_delay_us < 0 {
+            return Err(VisionError::new_err("delay_us and edge_delay_us must be >= 0"));
+        }
+        if min_count == 0 {
+            return Err(VisionError::new_err("min_count must be >= 1"));
+        }
+
+        let reader = open_input_reader(input_path)?;
+
+        let eff_delay = delay_us + edge_delay_us;
+
+        let compute: Result<String, ComputeError> = py.detach(|| {
+            let mut a_map: HashMap<(i64, i64, i64), Vec<i64>> = HashMap::new();
+            let mut b_map: HashMap<(i64, i64, i64), Vec<i64>> = HashMap::new();
+
+            for line in reader.lines() {
+                let line = line.map_err(|e| ComputeError::Io(format!("read failed: {e}")))?;
+                if line.trim().is_empty() {
+                    continue;
+                }
+                if serde_json::from_str::<InputHeader>(&line).is_ok() {
+                    continue;
+                }
+                if let Ok(ev) = serde_json::from_str::<InputEvent>(&line) {
+                    let ts = ev.ts;
+                    let x = ev.idx[0];
+                    let y = ev.idx[1];
+                    let pol = ev.idx[2];
+                    if x < 0 || y < 0 || !(0..=1).contains(&pol) {
+                        continue;
+                    }
+                    if (x as usize) >= width || (y as usize) >= height {
+                        continue;
+                    }
+
+                    a_map.entry((x, y, pol)).or_default().push(ts);
+
+                    let b_ts = ts.saturating_add(eff_delay);
+                    if x + 1 < width as i64 {
+                        b_map.entry((x + 1, y, pol)).or_default().push(b_ts);
+                    }
+                    if x > 0 {
+                        b_map.entry((x - 1, y, pol)).or_default().push(b_ts);
+                    }
+                }
+            }
+
+            for v in a_map.values_mut() {
+                v.sort_unstable();
+            }
+            for v in b_map.values_mut() {
+                v.sort_unstable();
+            }
+
+            let mut keys: HashSet<(i64, i64, i64)> = HashSet::new();
+            keys.extend(a_map.keys().cloned());
+            keys.extend(b_map.keys().cloned());
+
+            let mut out_events: Vec<(i64, i64, i64, i64)> = keys
+                .into_iter()
+                .flat_map(|(x, y, pol)| {
+                    let va = a_map.remove(&(x, y, pol)).unwrap_or_default();
+                    let vb = b_map.remove(&(x, y, pol)).unwrap_or_default();
+                    fuse_coordinate(x, y, pol, va, vb, window_us, min_count, true)
+                })
+                .collect();
+            out_events.sort_unstable();
+
+            let mut bytes = Vec::with_capacity(out_events.len() * 32);
+            for (ts, x, y, pol) in out_events {
+                bytes.extend_from_slice(&ts.to_le_bytes());
+                bytes.extend_from_slice(&x.to_le_bytes());
+                bytes.extend_from_slice(&y.to_le_bytes());
+                bytes.extend_from_slice(&pol.to_le_bytes());
+            }
+            Ok(format!("{:016x}", fnv1a_hash(&bytes)))
+        });
+        compute.map_err(ComputeError::into_py_err)
+    }
+
+    /// Like `optical_flow_shift_delay_fuse_arrays`, but takes the input
+    /// event columns directly as NumPy arrays instead of a JSONL file path,
+    /// so a caller that already has events in memory (e.g. chained from a
+    /// prior kernel) can skip the serialize-to-disk/re-parse round trip.
+    /// Runs the identical shift/delay/coincidence-fuse logic as
+    /// `optical_flow_shift_delay_fuse_coo` and returns the same columnar
+    /// `{ts, x, y, polarity, val}` dict. `ts`, `x`, `y`, and `pol` must all
+    /// have equal length. `require_both`: see `optical_flow_shift_delay_fuse_coo`.
+    #[pyfunction]
+    #[allow(clippy::too_many_arguments)]
+    #[pyo3(signature = (ts, x, y, pol, width, height, window_us, delay_us, edge_delay_us, min_count, require_both=true))]
+    fn optical_flow_shift_delay_fuse_arrays_in<'py>(
+        py: Python<'py>,
+        ts: PyReadonlyArray1<i64>,
+        x: PyReadonlyArray1<i64>,
+        y: PyReadonlyArray1<i64>,
+        pol: PyReadonlyArray1<i64>,
+        width: usize,
+        height: usize,
+        window_us: i64,
+        delay_us: i64,
+        edge_delay_us: i64,
+        min_count: usize,
+        require_both: bool,
+    ) -> PyResult<(Py<PyAny>, Py<PyAny>)> {
+        if width == 0 || height == 0 {
+            return Err(VisionError::new_err("width/height must be > 0"));
+        }
+        if window_us <= 0 {
+            return Err(VisionError::new_err("window_us must be > 0"));
+        }
+        if delay_us < 0 || edge_delay_us < 0 {
+            return Err(VisionError::new_err("delay_us and edge_delay_us must be >= 0"));
+        }
+        if min_count == 0 {
+            return Err(VisionError::new_err("min_count must be >= 1"));
+        }
+
+        let ts = ts.as_array();
+        let x = x.as_array();
+        let y = y.as_array();
+        let pol = pol.as_array();
+        let n = ts.len();
+        if x.len() != n || y.len() != n || pol.len() != n {
+            return Err(VisionError::new_err(format!(
+                "column length mismatch: ts={} x={} y={} pol={}",
+                n, x.len(), y.len(), pol.len()
+            )));
+        }
+
+        let eff_delay = delay_us + edge_delay_us;
+
+        let mut a_map: HashMap<(i64, i64, i64), Vec<i64>> = HashMap::new();
+        let mut b_map: HashMap<(i64, i64, i64), Vec<(i64, i8)>> = HashMap::new();
+
+        for i in 0..n {
+            let ts_i = ts[i];
+            let x_i = x[i];
+            let y_i = y[i];
+            let pol_i = pol[i];
+            if x_i < 0 || y_i < 0 || !(0..=1).contains(&pol_i) {
+                continue;
             }
-        }
-    
-        // Sort events for deterministic order
-        out_events.sort_unstable();
-    
-        // Build header dict
-        let hdr = PyDict::new(py);
-        hdr.set_item("schema_version", "0.1.0")?;
-        hdr.set_item("dims", vec!["x", "y", "polarity"])?;
-        let units = PyDict::new(py);
-        units.set_item("time", "us")?;
-        units.set_item("value", "dimensionless")?;
-        hdr.set_item("units", units)?;
-        hdr.set_item("dtype", "f32")?;
-        hdr.set_item("layout", "coo")?;
-        let md = PyDict::new(py);
-        md.set_item("backend", "native-rust")?;
-        md.set_item("kernel", "passthrough_events")?;
-        hdr.set_item("metadata", md)?;
-    
-        // Preserve dims from source header if present
-        if let Some(src_hdr) = header_opt {
-            if let Some(dims) = src_hdr.get("dims") {
-                if let Some(arr) = dims.as_array() {
-                    let py_dims = PyList::empty(py);
-                    for v in arr {
-                        if let Some(s) = v.as_str() {
-                            py_dims.append(s)?;
-                        } else if let Some(n) = v.as_i64() {
-                            py_dims.append(n)?;
-                        } else {
-                            py_dims.append(v.to_string())?;
-                        }
-                    }
-                    hdr.set_item("dims", py_dims)?;
-                }
+            if (x_i as usize) >= width || (y_i as usize) >= height {
+                continue;
+            }
+
+            a_map.entry((x_i, y_i, pol_i)).or_default().push(ts_i);
+
+            let b_ts = ts_i.saturating_add(eff_delay);
+            if x_i + 1 < width as i64 {
+                b_map.entry((x_i + 1, y_i, pol_i)).or_default().push((b_ts, 1));
+            }
+            if x_i > 0 {
+                b_map.entry((x_i - 1, y_i, pol_i)).or_default().push((b_ts, -1));
             }
         }
-    
-        // Build columns
-        let n = out_events.len();
+
+        for v in a_map.values_mut() {
+            v.sort_unstable();
+        }
+        for v in b_map.values_mut() {
+            v.sort_unstable_by_key(|&(t, _)| t);
+        }
+
+        let mut keys: HashSet<(i64, i64, i64)> = HashSet::new();
+        keys.extend(a_map.keys().cloned());
+        keys.extend(b_map.keys().cloned());
+
+        let coords: Vec<DirectedCoordInput> = keys
+            .into_iter()
+            .map(|k| {
+                let va = a_map.remove(&k).unwrap_or_default();
+                let vb = b_map.remove(&k).unwrap_or_default();
+                (k, va, vb)
+            })
+            .collect();
+
+        let per_coord: Vec<Vec<DirectedEvent>> = py.detach(|| {
+            use rayon::prelude::*;
+            coords
+                .into_par_iter()
+                .map(|((cx, cy, cpol), va, vb)| fuse_coordinate_directional(cx, cy, cpol, va, vb, window_us, min_count, require_both))
+                .collect()
+        });
+
+        let mut out_events: Vec<(i64, i64, i64, i64, i8)> = per_coord.into_iter().flatten().collect();
+        out_events.sort_unstable();
+
+        let hdr = build_output_header(py, &None, false, "optical_flow_shift_delay_fuse")?;
+        let arrays = columnar_dict(py, &out_events, false)?;
+        Ok((hdr.into(), arrays.unbind().into()))
+    }
+
+    /// Builds one `{ts, x, y, polarity, val}` dict of NumPy arrays from a
+    /// slice of fused events, shared by `optical_flow_shift_delay_fuse_arrays`'s
+    /// single-dict and chunked-list return modes so both stay byte-for-byte
+    /// identical to each other on overlapping events. When `with_direction`
+    /// is true, an additional `dir` column (i64) is included.
+    fn columnar_dict<'py>(
+        py: Python<'py>,
+        events: &[(i64, i64, i64, i64, i8)],
+        with_direction: bool,
+    ) -> PyResult<Bound<'py, PyDict>> {
+        let n = events.len();
         let mut ts_col: Vec<i64> = Vec::with_capacity(n);
         let mut x_col: Vec<i64> = Vec::with_capacity(n);
         let mut y_col: Vec<i64> = Vec::with_capacity(n);
         let mut pol_col: Vec<i64> = Vec::with_capacity(n);
         let mut val_col: Vec<f32> = Vec::with_capacity(n);
-    
-        for (ts, x, y, pol) in out_events.into_iter() {
+        let mut dir_col: Vec<i64> = Vec::with_capacity(n);
+
+        for &(ts, x, y, pol, dir) in events {
             ts_col.push(ts);
             x_col.push(x);
             y_col.push(y);
             pol_col.push(pol);
             val_col.push(1.0f32);
+            dir_col.push(dir as i64);
         }
-    
+
         let ts = PyArray1::<i64>::from_vec(py, ts_col);
         let x = PyArray1::<i64>::from_vec(py, x_col);
         let y = PyArray1::<i64>::from_vec(py, y_col);
         let polarity = PyArray1::<i64>::from_vec(py, pol_col);
         let val = PyArray1::<f32>::from_vec(py, val_col);
-    
+
         let arrays = PyDict::new(py);
         arrays.set_item("ts", ts)?;
         arrays.set_item("x", x)?;
         arrays.set_item("y", y)?;
         arrays.set_item("polarity", polarity)?;
         arrays.set_item("val", val)?;
-    
-        Ok((hdr.unbind().into(), arrays.unbind().into()))
+        if with_direction {
+            let dir = PyArray1::<i64>::from_vec(py, dir_col);
+            arrays.set_item("dir", dir)?;
+        }
+        Ok(arrays)
     }
-    
-    // Shift/Delay/Fuse optical flow that emits coincidences per coordinate
+
+    /// Counts, for each timestamp in `own`, whether a shifted/delayed neighbor
+    /// timestamp was within `window_us` at the moment it arrived in the merged
+    /// stream. Reuses the same merge-and-prune sliding window as
+    /// `fuse_coordinate`, but only tallies `own`-side hits so the result is
+    /// bounded by `own.len()` and can be normalized into a [0,1] score.
+    fn count_own_coincident(own: &[i64], neighbor: &[i64], window_us: i64) -> usize {
+        let mut merged: Vec<(i64, u8)> = Vec::with_capacity(own.len() + neighbor.len());
+        for &t in own {
+            merged.push((t, 0));
+        }
+        for &t in neighbor {
+            merged.push((t, 1));
+        }
+        merged.sort_unstable_by_key(|e| e.0);
+
+        let mut buf_own: VecDeque<i64> = VecDeque::new();
+        let mut buf_neighbor: VecDeque<i64> = VecDeque::new();
+        let mut hits = 0usize;
+
+        for (t, src) in merged.into_iter() {
+            if src == 0 {
+                buf_own.push_back(t);
+            } else {
+                buf_neighbor.push_back(t);
+            }
+            let cutoff = t.saturating_sub(window_us);
+            while let Some(&front) = buf_own.front() {
+                if front < cutoff {
+                    buf_own.pop_front();
+                } else {
+                    break;
+                }
+            }
+            while let Some(&front) = buf_neighbor.front() {
+                if front < cutoff {
+                    buf_neighbor.pop_front();
+                } else {
+                    break;
+                }
+            }
+            if src == 0 && !buf_own.is_empty() && !buf_neighbor.is_empty() {
+                hits += 1;
+            }
+        }
+
+        hits
+    }
+
+    /// Per-pixel directional selectivity index, in `[0, 1]`: the fraction of a
+    /// pixel's own events that coincide (within `window_us`) with a
+    /// `shift`-pixels-away, `delay_us`-delayed neighbor along x. Built on the
+    /// same shift/delay coincidence idea as `optical_flow_shift_delay_fuse_coo`,
+    /// but collapsed to a single scalar per pixel (own coincidence count over
+    /// own total event count) instead of an emitted event list, so it can be
+    /// overlaid directly as a heatmap without per-event post-processing in
+    /// Python. Pixels with zero events score 0.0. `shift` may be negative to
+    /// probe motion in the opposite x direction; `shift == 0` compares a pixel
+    /// against itself delayed by `delay_us`, which is degenerate but not an error.
     #[pyfunction]
-    #[allow(clippy::too_many_arguments)]
-    fn optical_flow_shift_delay_fuse_coo<'py>(
+    fn flow_strength_map<'py>(
         py: Python<'py>,
         input_path: &str,
         width: usize,
         height: usize,
         window_us: i64,
         delay_us: i64,
-        edge_delay_us: i64,
-        min_count: usize,
-    ) -> PyResult<(Py<PyAny>, Py<PyAny>)> {
+        shift: i64,
+    ) -> PyResult<Py<PyArray2<f32>>> {
         if width == 0 || height == 0 {
             return Err(VisionError::new_err("width/height must be > 0"));
         }
         if window_us <= 0 {
             return Err(VisionError::new_err("window_us must be > 0"));
         }
-        if delay_us < 0 || edge_delay_us < 0 {
-            return Err(VisionError::new_err("delay_us and edge_delay_us must be >= 0"));
-        }
-        if min_count == 0 {
-            return Err(VisionError::new_err("min_count must be >= 1"));
+        if delay_us < 0 {
+            return Err(VisionError::new_err("delay_us must be >= 0"));
         }
-    
-        let file = File::open(input_path).map_err(|e| PyIOError::new_err(format!("open failed: {e}")))?;
-        let reader = BufReader::new(file);
-    
-        let eff_delay = delay_us + edge_delay_us;
-    
-        // Optional header passthrough
-        let mut header_opt: Option<Value> = None;
-    
-        // Per-coordinate event times for A (source) and B (neighbor-shifted, delayed)
-        let mut a_map: HashMap<(i64, i64, i64), Vec<i64>> = HashMap::new();
-        let mut b_map: HashMap<(i64, i64, i64), Vec<i64>> = HashMap::new();
-    
+
+        let reader = open_input_reader(input_path)?;
+
+        // own_map: this pixel's own event timestamps.
+        // neighbor_map: delayed timestamps contributed by the pixel `shift`
+        // columns to the left, i.e. neighbor_map[(x,y)] holds (x - shift, y)'s
+        // events shifted forward by delay_us, representing motion that would
+        // arrive at (x,y) if it were moving in the +shift direction.
+        let mut own_map: HashMap<(i64, i64), Vec<i64>> = HashMap::new();
+        let mut neighbor_map: HashMap<(i64, i64), Vec<i64>> = HashMap::new();
+
         for line in reader.lines() {
             let line = line.map_err(|e| PyIOError::new_err(format!("read failed: {e}")))?;
             if line.trim().is_empty() {
                 continue;
             }
-            // Capture header if present
-            if header_opt.is_none() {
-                if let Ok(h) = serde_json::from_str::<InputHeader>(&line) {
-                    header_opt = Some(h.header);
-                    continue;
-                }
-            }
-            // Parse event
             if let Ok(ev) = serde_json::from_str::<InputEvent>(&line) {
-                let ts = ev.ts;
                 let x = ev.idx[0];
                 let y = ev.idx[1];
-                let pol = ev.idx[2];
-                if x < 0 || y < 0 || pol < 0 || pol > 1 {
-                    continue;
-                }
-                if (x as usize) >= width || (y as usize) >= height {
+                if x < 0 || y < 0 || (x as usize) >= width || (y as usize) >= height {
                     continue;
                 }
-    
-                // A-stream at (x,y,pol)
-                a_map.entry((x, y, pol)).or_default().push(ts);
-    
-                // B-stream: shift ±1 in x and delay by eff_delay
-                let b_ts = ts.saturating_add(eff_delay);
-                if x + 1 < width as i64 {
-                    b_map.entry((x + 1, y, pol)).or_default().push(b_ts);
-                }
-                if x > 0 {
-                    b_map.entry((x - 1, y, pol)).or_default().push(b_ts);
+                own_map.entry((x, y)).or_default().push(ev.ts);
+
+                let nx = x + shift;
+                if nx >= 0 && (nx as usize) < width {
+                    neighbor_map.entry((nx, y)).or_default().push(ev.ts.saturating_add(delay_us));
                 }
             }
         }
-    
-        // For determinism, sort the per-key vectors
-        for v in a_map.values_mut() {
+
+        for v in own_map.values_mut() {
             v.sort_unstable();
         }
-        for v in b_map.values_mut() {
+        for v in neighbor_map.values_mut() {
             v.sort_unstable();
         }
-    
-        // Process each coordinate independently with a sliding window coincidence fuse
-        let mut out_events: Vec<(i64, i64, i64, i64)> = Vec::new();
-        let mut seen: HashSet<(i64, i64, i64, i64)> = HashSet::new();
-    
-        let mut keys: HashSet<(i64, i64, i64)> = HashSet::new();
-        keys.extend(a_map.keys().cloned());
-        keys.extend(b_map.keys().cloned());
-    
-        for (x, y, pol) in keys.into_iter() {
-            let va = a_map.remove(&(x, y, pol)).unwrap_or_default();
-            let vb = b_map.remove(&(x, y, pol)).unwrap_or_default();
-    
-            // Merge timestamps with source tags (0 for A, 1 for B)
-            let mut merged: Vec<(i64, u8)> = Vec::with_capacity(va.len() + vb.len());
-            for t in va.into_iter() {
-                merged.push((t, 0));
-            }
-            for t in vb.into_iter() {
-                merged.push((t, 1));
-            }
-            merged.sort_unstable_by_key(|e| e.0);
-    
-            let mut buf_a: VecDeque<i64> = VecDeque::new();
-            let mut buf_b: VecDeque<i64> = VecDeque::new();
-    
-            for (t, src) in merged.into_iter() {
-                if src == 0 {
-                    buf_a.push_back(t);
-                } else {
-                    buf_b.push_back(t);
-                }
-                let cutoff = t.saturating_sub(window_us);
-    
-                // Prune strictly older than (t - window)
-                while let Some(&front) = buf_a.front() {
-                    if front < cutoff {
-                        buf_a.pop_front();
-                    } else {
-                        break;
-                    }
-                }
-                while let Some(&front) = buf_b.front() {
-                    if front < cutoff {
-                        buf_b.pop_front();
-                    } else {
-                        break;
-                    }
-                }
-    
-                let total = buf_a.len() + buf_b.len();
-                if total >= min_count && !buf_a.is_empty() && !buf_b.is_empty() {
-                    if seen.insert((t, x, y, pol)) {
-                        out_events.push((t, x, y, pol));
-                    }
-                }
-            }
-        }
-    
-        // Sort outputs for deterministic return order
-        out_events.sort_unstable();
-    
-        // Build header dict (compatible with golden schema)
-        let hdr = PyDict::new(py);
-        hdr.set_item("schema_version", "0.1.0")?;
-        hdr.set_item("dims", vec!["x", "y", "polarity"])?;
-        let units = PyDict::new(py);
-        units.set_item("time", "us")?;
-        units.set_item("value", "dimensionless")?;
-        hdr.set_item("units", units)?;
-        hdr.set_item("dtype", "f32")?;
-        hdr.set_item("layout", "coo")?;
-        let md = PyDict::new(py);
-        md.set_item("backend", "native-rust")?;
-        md.set_item("kernel", "optical_flow_shift_delay_fuse")?;
-        hdr.set_item("metadata", md)?;
-    
-        // Preserve dims from source header if present
-        if let Some(src_hdr) = header_opt {
-            if let Some(dims) = src_hdr.get("dims") {
-                if let Some(arr) = dims.as_array() {
-                    let py_dims = PyList::empty(py);
-                    for v in arr {
-                        if let Some(s) = v.as_str() {
-                            py_dims.append(s)?;
-                        } else if let Some(n) = v.as_i64() {
-                            py_dims.append(n)?;
-                        } else {
-                            py_dims.append(v.to_string())?;
-                        }
-                    }
-                    hdr.set_item("dims", py_dims)?;
-                }
-            }
-        }
-    
-        // Build events list
-        let ev_list = PyList::empty(py);
-        for (ts, x, y, pol) in out_events {
-            let d = PyDict::new(py);
-            d.set_item("ts", ts)?;
-            d.set_item("idx", vec![x, y, pol])?;
-            d.set_item("val", 1.0f32)?;
-            ev_list.append(d)?;
+
+        let out = PyArray2::<f32>::zeros(py, (height, width), false);
+        // SAFETY: out is newly allocated with exclusive ownership while holding the GIL
+        let mut out_view = unsafe { out.as_array_mut() };
+        for (&(x, y), own) in own_map.iter() {
+            let empty = Vec::new();
+            let neighbor = neighbor_map.get(&(x, y)).unwrap_or(&empty);
+            let hits = count_own_coincident(own, neighbor, window_us);
+            out_view[[y as usize, x as usize]] = hits as f32 / own.len() as f32;
         }
-    
-        Ok((hdr.unbind().into(), ev_list.unbind().into()))
+
+        Ok(out.unbind())
     }
 
-    /// Shift/Delay/Fuse returning columnar NumPy arrays (ts, x, y, polarity, val)
+    /// Per-pixel ON/OFF coincidence fuse: distinct connectivity from the neighbor
+    /// shift/delay kernels above, this fuses the ON (pol=1) and OFF (pol=0) timestamp
+    /// streams at the *same* (x, y), not across neighbors, to detect fast local
+    /// polarity flicker that directional flow kernels miss. Reuses `fuse_coordinate`'s
+    /// sliding-window coincidence logic with the ON stream as A and the OFF stream as
+    /// B; emitted events are tagged `pol=2` since they represent neither polarity alone.
     #[pyfunction]
-    #[allow(clippy::too_many_arguments)]
-    fn optical_flow_shift_delay_fuse_arrays<'py>(
+    #[allow(clippy::type_complexity)]
+    #[pyo3(signature = (input_path, width, height, window_us, min_count, preserve_header=false))]
+    fn onoff_coincidence<'py>(
         py: Python<'py>,
         input_path: &str,
         width: usize,
         height: usize,
         window_us: i64,
-        delay_us: i64,
-        edge_delay_us: i64,
         min_count: usize,
+        preserve_header: bool,
     ) -> PyResult<(Py<PyAny>, Py<PyAny>)> {
         if width == 0 || height == 0 {
             return Err(VisionError::new_err("width/height must be > 0"));
@@ -489,184 +3687,334 @@ fn optical_flow_coo_from_jsonl<'py>(
         if window_us <= 0 {
             return Err(VisionError::new_err("window_us must be > 0"));
         }
-        if delay_us < 0 || edge_delay_us < 0 {
-            return Err(VisionError::new_err("delay_us and edge_delay_us must be >= 0"));
-        }
         if min_count == 0 {
             return Err(VisionError::new_err("min_count must be >= 1"));
         }
 
-        let file = File::open(input_path).map_err(|e| PyIOError::new_err(format!("open failed: {e}")))?;
-        let reader = BufReader::new(file);
-
-        let eff_delay = delay_us + edge_delay_us;
+        let reader = open_input_reader(input_path)?;
 
-        // Optional header passthrough
         let mut header_opt: Option<Value> = None;
 
-        // Per-coordinate event times for A (source) and B (neighbor-shifted, delayed)
-        let mut a_map: HashMap<(i64, i64, i64), Vec<i64>> = HashMap::new();
-        let mut b_map: HashMap<(i64, i64, i64), Vec<i64>> = HashMap::new();
+        // Per-pixel ON (pol=1) and OFF (pol=0) event times
+        let mut on_map: HashMap<(i64, i64), Vec<i64>> = HashMap::new();
+        let mut off_map: HashMap<(i64, i64), Vec<i64>> = HashMap::new();
 
         for line in reader.lines() {
             let line = line.map_err(|e| PyIOError::new_err(format!("read failed: {e}")))?;
             if line.trim().is_empty() {
                 continue;
             }
-            // Capture header if present
             if header_opt.is_none() {
                 if let Ok(h) = serde_json::from_str::<InputHeader>(&line) {
                     header_opt = Some(h.header);
                     continue;
                 }
             }
-            // Parse event
             if let Ok(ev) = serde_json::from_str::<InputEvent>(&line) {
                 let ts = ev.ts;
                 let x = ev.idx[0];
                 let y = ev.idx[1];
                 let pol = ev.idx[2];
-                if x < 0 || y < 0 || pol < 0 || pol > 1 {
+                if x < 0 || y < 0 || !(0..=1).contains(&pol) {
                     continue;
                 }
                 if (x as usize) >= width || (y as usize) >= height {
                     continue;
                 }
-
-                // A-stream at (x,y,pol)
-                a_map.entry((x, y, pol)).or_default().push(ts);
-
-                // B-stream: shift ±1 in x and delay by eff_delay
-                let b_ts = ts.saturating_add(eff_delay);
-                if x + 1 < width as i64 {
-                    b_map.entry((x + 1, y, pol)).or_default().push(b_ts);
-                }
-                if x > 0 {
-                    b_map.entry((x - 1, y, pol)).or_default().push(b_ts);
+                if pol == 1 {
+                    on_map.entry((x, y)).or_default().push(ts);
+                } else {
+                    off_map.entry((x, y)).or_default().push(ts);
                 }
             }
         }
 
-        // For determinism, sort the per-key vectors
-        for v in a_map.values_mut() {
+        for v in on_map.values_mut() {
             v.sort_unstable();
         }
-        for v in b_map.values_mut() {
+        for v in off_map.values_mut() {
             v.sort_unstable();
         }
 
-        // Process each coordinate independently with a sliding window coincidence fuse
-        let mut out_events: Vec<(i64, i64, i64, i64)> = Vec::new();
-        let mut seen: HashSet<(i64, i64, i64, i64)> = HashSet::new();
+        let mut keys: HashSet<(i64, i64)> = HashSet::new();
+        keys.extend(on_map.keys().cloned());
+        keys.extend(off_map.keys().cloned());
 
-        let mut keys: HashSet<(i64, i64, i64)> = HashSet::new();
-        keys.extend(a_map.keys().cloned());
-        keys.extend(b_map.keys().cloned());
+        let coords: Vec<((i64, i64), Vec<i64>, Vec<i64>)> = keys
+            .into_iter()
+            .map(|k| {
+                let va = on_map.remove(&k).unwrap_or_default();
+                let vb = off_map.remove(&k).unwrap_or_default();
+                (k, va, vb)
+            })
+            .collect();
 
-        for (x, y, pol) in keys.into_iter() {
-            let va = a_map.remove(&(x, y, pol)).unwrap_or_default();
-            let vb = b_map.remove(&(x, y, pol)).unwrap_or_default();
+        let per_coord: Vec<Vec<(i64, i64, i64, i64)>> = py.detach(|| {
+            use rayon::prelude::*;
+            coords
+                .into_par_iter()
+                .map(|((x, y), va, vb)| fuse_coordinate(x, y, 2, va, vb, window_us, min_count, true))
+                .collect()
+        });
 
-            // Merge timestamps with source tags (0 for A, 1 for B)
-            let mut merged: Vec<(i64, u8)> = Vec::with_capacity(va.len() + vb.len());
-            for t in va.into_iter() { merged.push((t, 0)); }
-            for t in vb.into_iter() { merged.push((t, 1)); }
-            merged.sort_unstable_by_key(|e| e.0);
+        let mut out_events: Vec<(i64, i64, i64, i64)> = per_coord.into_iter().flatten().collect();
+        out_events.sort_unstable();
 
-            let mut buf_a: VecDeque<i64> = VecDeque::new();
-            let mut buf_b: VecDeque<i64> = VecDeque::new();
+        let hdr = build_output_header(py, &header_opt, preserve_header, "onoff_coincidence")?;
 
-            for (t, src) in merged.into_iter() {
-                if src == 0 { buf_a.push_back(t); } else { buf_b.push_back(t); }
-                let cutoff = t.saturating_sub(window_us);
+        let ev_list = PyList::empty(py);
+        for (ts, x, y, pol) in out_events.into_iter() {
+            let d = PyDict::new(py);
+            d.set_item("ts", ts)?;
+            d.set_item("idx", vec![x, y, pol])?;
+            d.set_item("val", 1.0f32)?;
+            ev_list.append(d)?;
+        }
 
-                // Prune strictly older than (t - window)
-                while let Some(&front) = buf_a.front() {
-                    if front < cutoff { buf_a.pop_front(); } else { break; }
-                }
-                while let Some(&front) = buf_b.front() {
-                    if front < cutoff { buf_b.pop_front(); } else { break; }
-                }
+        Ok((hdr.into(), ev_list.unbind().into()))
+    }
 
-                let total = buf_a.len() + buf_b.len();
-                if total >= min_count && !buf_a.is_empty() && !buf_b.is_empty() {
-                    if seen.insert((t, x, y, pol)) {
-                        out_events.push((t, x, y, pol));
-                    }
-                }
+    /// Convert a Python object into the equivalent serde_json Value.
+    /// Supports the JSON-primitive subset (None/bool/int/float/str/list/dict)
+    /// needed for header `units`/`metadata` payloads.
+    fn py_to_json(v: &Bound<PyAny>) -> PyResult<Value> {
+        if v.is_none() {
+            Ok(Value::Null)
+        } else if let Ok(b) = v.extract::<bool>() {
+            Ok(Value::Bool(b))
+        } else if let Ok(i) = v.extract::<i64>() {
+            Ok(Value::from(i))
+        } else if let Ok(f) = v.extract::<f64>() {
+            Ok(Value::from(f))
+        } else if let Ok(s) = v.extract::<String>() {
+            Ok(Value::String(s))
+        } else if let Ok(list) = v.downcast::<PyList>() {
+            let mut arr = Vec::with_capacity(list.len());
+            for item in list.iter() {
+                arr.push(py_to_json(&item)?);
+            }
+            Ok(Value::Array(arr))
+        } else if let Ok(dict) = v.downcast::<PyDict>() {
+            let mut map = serde_json::Map::with_capacity(dict.len());
+            for (k, val) in dict.iter() {
+                let key: String = k.extract()?;
+                map.insert(key, py_to_json(&val)?);
             }
+            Ok(Value::Object(map))
+        } else {
+            Err(PyValueError::new_err("unsupported value in header units/metadata"))
         }
+    }
 
-        // Sort outputs for deterministic return order
-        out_events.sort_unstable();
+    /// Streaming JSONL writer that validates events against the trace's
+    /// declared `dims` as they are written, so golden traces generated from
+    /// Rust can't drift from the schema the way hand-formatted JSON strings do.
+    #[pyclass]
+    struct UecWriter {
+        writer: Option<std::io::BufWriter<File>>,
+        dims: Vec<String>,
+    }
 
-        // Build header dict (compatible with golden schema)
-        let hdr = PyDict::new(py);
-        hdr.set_item("schema_version", "0.1.0")?;
-        hdr.set_item("dims", vec!["x", "y", "polarity"])?;
-        let units = PyDict::new(py);
-        units.set_item("time", "us")?;
-        units.set_item("value", "dimensionless")?;
-        hdr.set_item("units", units)?;
-        hdr.set_item("dtype", "f32")?;
-        hdr.set_item("layout", "coo")?;
-        let md = PyDict::new(py);
-        md.set_item("backend", "native-rust")?;
-        md.set_item("kernel", "optical_flow_shift_delay_fuse")?;
-        hdr.set_item("metadata", md)?;
-
-        // Preserve dims from source header if present
-        if let Some(src_hdr) = header_opt {
-            if let Some(dims) = src_hdr.get("dims") {
-                if let Some(arr) = dims.as_array() {
-                    let py_dims = PyList::empty(py);
-                    for v in arr {
-                        if let Some(s) = v.as_str() {
-                            py_dims.append(s)?;
-                        } else if let Some(n) = v.as_i64() {
-                            py_dims.append(n)?;
-                        } else {
-                            py_dims.append(v.to_string())?;
-                        }
+    #[pymethods]
+    impl UecWriter {
+        #[new]
+        fn new(path: &str, dims: Vec<String>, units: Py<PyDict>) -> PyResult<Self> {
+            let file = File::create(path).map_err(|e| PyIOError::new_err(format!("create failed: {e}")))?;
+            let mut writer = std::io::BufWriter::new(file);
+
+            Python::attach(|py| -> PyResult<()> {
+                let units_json = py_to_json(units.bind(py).as_any())?;
+                let header = serde_json::json!({
+                    "header": {
+                        "schema_version": "0.1.0",
+                        "dims": dims,
+                        "units": units_json,
                     }
-                    hdr.set_item("dims", py_dims)?;
-                }
+                });
+                use std::io::Write;
+                writeln!(writer, "{header}").map_err(|e| PyIOError::new_err(format!("write failed: {e}")))
+            })?;
+
+            Ok(UecWriter { writer: Some(writer), dims })
+        }
+
+        fn write_event(&mut self, ts: i64, idx: Vec<i64>, val: f32) -> PyResult<()> {
+            if idx.len() != self.dims.len() {
+                return Err(VisionError::new_err(format!(
+                    "idx arity {} does not match dims arity {} ({:?})",
+                    idx.len(),
+                    self.dims.len(),
+                    self.dims
+                )));
             }
+            let writer = self
+                .writer
+                .as_mut()
+                .ok_or_else(|| VisionError::new_err("write_event called after close()"))?;
+            let line = serde_json::json!({"ts": ts, "idx": idx, "val": val});
+            use std::io::Write;
+            writeln!(writer, "{line}").map_err(|e| PyIOError::new_err(format!("write failed: {e}")))
         }
 
-        // Build columnar arrays
-        let n = out_events.len();
-        let mut ts_col: Vec<i64> = Vec::with_capacity(n);
-        let mut x_col: Vec<i64> = Vec::with_capacity(n);
-        let mut y_col: Vec<i64> = Vec::with_capacity(n);
-        let mut pol_col: Vec<i64> = Vec::with_capacity(n);
-        let mut val_col: Vec<f32> = Vec::with_capacity(n);
+        fn close(&mut self) -> PyResult<()> {
+            if let Some(mut w) = self.writer.take() {
+                use std::io::Write;
+                w.flush().map_err(|e| PyIOError::new_err(format!("flush failed: {e}")))?;
+            }
+            Ok(())
+        }
+    }
 
-        for (ts, x, y, pol) in out_events.into_iter() {
-            ts_col.push(ts);
-            x_col.push(x);
-            y_col.push(y);
-            pol_col.push(pol);
-            val_col.push(1.0f32);
+    /// Stateful per-pixel event surface that integrates each event with
+    /// exponential decay, distinct from the discrete time surface built fresh
+    /// per frame elsewhere in this crate: `update`/`update_batch` accumulate
+    /// signed polarity contributions, and `snapshot(t_ref)` decays every pixel
+    /// to `t_ref` on read without mutating state, so an online tracker can
+    /// query the surface at an arbitrary time without rebuilding it.
+    #[pyclass]
+    struct EventSurface {
+        width: usize,
+        height: usize,
+        tau_us: f64,
+        values: Vec<f32>,
+        last_ts: Vec<i64>,
+        touched: Vec<bool>,
+    }
+
+    #[pymethods]
+    impl EventSurface {
+        #[new]
+        fn new(width: usize, height: usize, tau_us: f64) -> PyResult<Self> {
+            if width == 0 || height == 0 {
+                return Err(VisionError::new_err("width/height must be > 0"));
+            }
+            if tau_us <= 0.0 {
+                return Err(VisionError::new_err("tau_us must be > 0"));
+            }
+            let n = width * height;
+            Ok(EventSurface {
+                width,
+                height,
+                tau_us,
+                values: vec![0.0; n],
+                last_ts: vec![0; n],
+                touched: vec![false; n],
+            })
         }
 
-        // Convert to NumPy arrays
-        let ts = PyArray1::<i64>::from_vec(py, ts_col);
-        let x = PyArray1::<i64>::from_vec(py, x_col);
-        let y = PyArray1::<i64>::from_vec(py, y_col);
-        let polarity = PyArray1::<i64>::from_vec(py, pol_col);
-        let val = PyArray1::<f32>::from_vec(py, val_col);
+        fn update(&mut self, ts: i64, x: i64, y: i64, pol: i64) -> PyResult<()> {
+            if x < 0 || (x as usize) >= self.width || y < 0 || (y as usize) >= self.height {
+                return Err(VisionError::new_err(format!(
+                    "coordinate ({x}, {y}) out of bounds for {}x{}",
+                    self.width, self.height
+                )));
+            }
+            let idx = (y as usize) * self.width + (x as usize);
+            let sign = if pol != 0 { 1.0f32 } else { -1.0f32 };
+            if self.touched[idx] {
+                let dt = (ts - self.last_ts[idx]) as f64;
+                let decay = (-dt / self.tau_us).exp() as f32;
+                self.values[idx] = self.values[idx] * decay + sign;
+            } else {
+                self.values[idx] = sign;
+                self.touched[idx] = true;
+            }
+            self.last_ts[idx] = ts;
+            Ok(())
+        }
 
-        let arrays = PyDict::new(py);
-        arrays.set_item("ts", ts)?;
-        arrays.set_item("x", x)?;
-        arrays.set_item("y", y)?;
-        arrays.set_item("polarity", polarity)?;
-        arrays.set_item("val", val)?;
+        fn update_batch(
+            &mut self,
+            ts: PyReadonlyArray1<i64>,
+            x: PyReadonlyArray1<i64>,
+            y: PyReadonlyArray1<i64>,
+            pol: PyReadonlyArray1<i64>,
+        ) -> PyResult<()> {
+            let ts = ts.as_array();
+            let x = x.as_array();
+            let y = y.as_array();
+            let pol = pol.as_array();
+            let n = ts.len();
+            if x.len() != n || y.len() != n || pol.len() != n {
+                return Err(VisionError::new_err(format!(
+                    "column length mismatch: ts={} x={} y={} pol={}",
+                    n, x.len(), y.len(), pol.len()
+                )));
+            }
+            for i in 0..n {
+                self.update(ts[i], x[i], y[i], pol[i])?;
+            }
+            Ok(())
+        }
 
-        Ok((hdr.unbind().into(), arrays.unbind().into()))
+        fn snapshot<'py>(&self, py: Python<'py>, t_ref: i64) -> PyResult<Py<PyArray2<f32>>> {
+            let out = PyArray2::<f32>::zeros(py, (self.height, self.width), false);
+            // SAFETY: out is newly allocated with exclusive ownership while holding the GIL
+            let mut out_view = unsafe { out.as_array_mut() };
+            for y in 0..self.height {
+                for x in 0..self.width {
+                    let idx = y * self.width + x;
+                    if self.touched[idx] {
+                        let dt = (t_ref - self.last_ts[idx]) as f64;
+                        let decay = (-dt / self.tau_us).exp() as f32;
+                        out_view[[y, x]] = self.values[idx] * decay;
+                    }
+                }
+            }
+            Ok(out.unbind())
+        }
     }
-    
+
+    /// Writes columnar event arrays directly to CSV (header row
+    /// `ts,x,y,polarity,val` followed by one line per event), avoiding the
+    /// per-row Python overhead of building the CSV from the numpy dict in
+    /// the caller. `val_precision` controls the number of decimal places
+    /// used for the `val` column.
+    #[pyfunction]
+    #[pyo3(signature = (output_path, ts, x, y, polarity, val, val_precision=6))]
+    #[allow(clippy::too_many_arguments)]
+    fn write_columns_csv(
+        output_path: &str,
+        ts: PyReadonlyArray1<i64>,
+        x: PyReadonlyArray1<i64>,
+        y: PyReadonlyArray1<i64>,
+        polarity: PyReadonlyArray1<i64>,
+        val: PyReadonlyArray1<f32>,
+        val_precision: usize,
+    ) -> PyResult<()> {
+        let ts = ts.as_array();
+        let x = x.as_array();
+        let y = y.as_array();
+        let polarity = polarity.as_array();
+        let val = val.as_array();
+        let n = ts.len();
+        if x.len() != n || y.len() != n || polarity.len() != n || val.len() != n {
+            return Err(VisionError::new_err(format!(
+                "column length mismatch: ts={} x={} y={} polarity={} val={}",
+                n,
+                x.len(),
+                y.len(),
+                polarity.len(),
+                val.len()
+            )));
+        }
+
+        let file = File::create(output_path).map_err(|e| PyIOError::new_err(format!("create failed: {e}")))?;
+        let mut writer = std::io::BufWriter::new(file);
+        use std::io::Write;
+        writeln!(writer, "ts,x,y,polarity,val").map_err(|e| PyIOError::new_err(format!("write failed: {e}")))?;
+        for i in 0..n {
+            writeln!(
+                writer,
+                "{},{},{},{},{:.*}",
+                ts[i], x[i], y[i], polarity[i], val_precision, val[i]
+            )
+            .map_err(|e| PyIOError::new_err(format!("write failed: {e}")))?;
+        }
+        writer.flush().map_err(|e| PyIOError::new_err(format!("flush failed: {e}")))?;
+        Ok(())
+    }
+
     #[pymodule]
     fn _vision_native(m: &Bound<PyModule>) -> PyResult<()> {
         let py = m.py();
@@ -676,11 +4024,43 @@ fn optical_flow_coo_from_jsonl<'py>(
         // Functions
         m.add_function(wrap_pyfunction!(is_ready, m)?)?;
         m.add_function(wrap_pyfunction!(optical_flow_stub, m)?)?;
+        m.add_function(wrap_pyfunction!(row_event_counts, m)?)?;
+        m.add_function(wrap_pyfunction!(col_event_counts, m)?)?;
+        m.add_function(wrap_pyfunction!(event_set_metrics, m)?)?;
+        m.add_function(wrap_pyfunction!(dominant_polarity_image, m)?)?;
+        m.add_function(wrap_pyfunction!(render_polarity_rgb, m)?)?;
+        m.add_function(wrap_pyfunction!(load_raw_events_binary, m)?)?;
+        m.add_function(wrap_pyfunction!(centroid_trajectory, m)?)?;
+        m.add_function(wrap_pyfunction!(activity_bbox, m)?)?;
+        m.add_function(wrap_pyfunction!(polarity_change_events, m)?)?;
+        m.add_function(wrap_pyfunction!(denoise_background_activity, m)?)?;
+        m.add_function(wrap_pyfunction!(event_corners, m)?)?;
+        m.add_function(wrap_pyfunction!(refractory_filter, m)?)?;
+        m.add_function(wrap_pyfunction!(remove_hot_pixels, m)?)?;
+        m.add_function(wrap_pyfunction!(resample_to_rate, m)?)?;
+        m.add_function(wrap_pyfunction!(roi_event_rates, m)?)?;
+        m.add_function(wrap_pyfunction!(peak_rate, m)?)?;
+        m.add_function(wrap_pyfunction!(rate_autocorrelation, m)?)?;
         m.add_function(wrap_pyfunction!(optical_flow_coo_from_jsonl, m)?)?;
         m.add_function(wrap_pyfunction!(optical_flow_coo_arrays, m)?)?;
+        m.add_function(wrap_pyfunction!(split_by_polarity, m)?)?;
         m.add_function(wrap_pyfunction!(optical_flow_shift_delay_fuse_coo, m)?)?;
+        m.add_function(wrap_pyfunction!(optical_flow_shift_delay_fuse_to_jsonl, m)?)?;
         m.add_function(wrap_pyfunction!(optical_flow_shift_delay_fuse_arrays, m)?)?;
+        m.add_function(wrap_pyfunction!(optical_flow_shift_delay_fuse_arrays_in, m)?)?;
+        m.add_function(wrap_pyfunction!(hash_events, m)?)?;
+        m.add_function(wrap_pyfunction!(flow_strength_map, m)?)?;
+        m.add_function(wrap_pyfunction!(onoff_coincidence, m)?)?;
+        m.add_function(wrap_pyfunction!(event_age_map, m)?)?;
+        m.add_function(wrap_pyfunction!(time_surface, m)?)?;
+        m.add_function(wrap_pyfunction!(accumulate_frame, m)?)?;
+        m.add_function(wrap_pyfunction!(motion_energy_maps, m)?)?;
+        m.add_function(wrap_pyfunction!(spatial_downsample, m)?)?;
         m.add_function(wrap_pyfunction!(set_log_sink, m)?)?;
+        m.add_function(wrap_pyfunction!(set_log_level, m)?)?;
         m.add_function(wrap_pyfunction!(log_emit, m)?)?;
+        m.add_function(wrap_pyfunction!(write_columns_csv, m)?)?;
+        m.add_class::<UecWriter>()?;
+        m.add_class::<EventSurface>()?;
         Ok(())
     }
\ No newline at end of file